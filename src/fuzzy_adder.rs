@@ -0,0 +1,168 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_int::FInt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Adder {
+    RippleCarry,
+    CarryLookahead,
+    KoggeStone,
+    CarrySelect,
+}
+
+impl Adder {
+    pub const ALL: [Adder; 4] = [
+        Adder::RippleCarry,
+        Adder::CarryLookahead,
+        Adder::KoggeStone,
+        Adder::CarrySelect,
+    ];
+}
+
+const BLOCK_SIZE: usize = 4;
+
+// Each carry inside a block is expanded into its full sum of products form,
+// blocks are then chained together by their carry out
+fn carry_lookahead<const L: usize>(a: &FInt<L>, b: &FInt<L>) -> Vec<FBit> {
+    let generate = (0..L).map(|i| (&a[i] & &b[i]).clone()).collect::<Vec<_>>();
+    let propagate = (0..L).map(|i| (&a[i] ^ &b[i]).clone()).collect::<Vec<_>>();
+
+    let mut carries = vec![FBit::FALSE; L + 1];
+
+    for start in (0..L).step_by(BLOCK_SIZE) {
+        let end = (start + BLOCK_SIZE).min(L);
+
+        for i in start + 1..=end {
+            let mut carry = FBit::FALSE;
+
+            for j in start..i {
+                let mut term = generate[j].clone();
+                for k in j + 1..i {
+                    term = (&term & &propagate[k]).clone();
+                }
+                carry = (&carry | &term).clone();
+            }
+
+            let mut term = carries[start].clone();
+            for k in start..i {
+                term = (&term & &propagate[k]).clone();
+            }
+
+            carries[i] = (&carry | &term).clone();
+        }
+    }
+
+    carries
+}
+
+// Parallel prefix over (generate, propagate) pairs with doubling spans
+fn kogge_stone<const L: usize>(a: &FInt<L>, b: &FInt<L>) -> Vec<FBit> {
+    let mut generate = (0..L).map(|i| (&a[i] & &b[i]).clone()).collect::<Vec<_>>();
+    let mut propagate = (0..L).map(|i| (&a[i] ^ &b[i]).clone()).collect::<Vec<_>>();
+
+    let mut span = 1;
+    while span < L {
+        let previous_generate = generate.clone();
+        let previous_propagate = propagate.clone();
+
+        for i in span..L {
+            generate[i] = (&previous_generate[i]
+                | (&previous_propagate[i] & &previous_generate[i - span]))
+                .clone();
+            propagate[i] = (&previous_propagate[i] & &previous_propagate[i - span]).clone();
+        }
+
+        span *= 2;
+    }
+
+    let mut carries = vec![FBit::FALSE];
+    carries.extend(generate);
+    carries
+}
+
+// Each block is summed for both possible carry ins, and the real carry picks between them
+fn carry_select<const L: usize>(a: &FInt<L>, b: &FInt<L>) -> Vec<FBit> {
+    let mut carries = vec![FBit::FALSE; L + 1];
+
+    for start in (0..L).step_by(BLOCK_SIZE) {
+        let end = (start + BLOCK_SIZE).min(L);
+
+        let mut carry_zero = FBit::FALSE;
+        let mut carry_one = FBit::TRUE;
+
+        for i in start..end {
+            FBit::add_carry(&a[i], &b[i], &mut carry_zero);
+            FBit::add_carry(&a[i], &b[i], &mut carry_one);
+
+            carries[i + 1] = carries[start].piecewise(&carry_one, &carry_zero).clone();
+        }
+    }
+
+    carries
+}
+
+impl<const L: usize> FInt<L> {
+    pub fn add_with(&self, rhs: &Self, adder: Adder) -> &Self {
+        let carries = match adder {
+            Adder::RippleCarry => return self + rhs,
+            Adder::CarryLookahead => carry_lookahead(self, rhs),
+            Adder::KoggeStone => kogge_stone(self, rhs),
+            Adder::CarrySelect => carry_select(self, rhs),
+        };
+
+        FInt::build(|i| &self[i] ^ &rhs[i] ^ &carries[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_int::*;
+
+    #[test]
+    fn test_adders_agree() {
+        let test_cases: Vec<(usize, usize)> = vec![
+            (0, 0),
+            (20, 10),
+            (0xFFFFFFFF, 1),
+            (0xDEADBEEF, 0x12345678),
+            (0x7FFFFFFF, 0x7FFFFFFF),
+        ];
+
+        for (a, b) in test_cases {
+            for adder in Adder::ALL {
+                let result = FInt32::from(a).add_with(FInt32::from(b), adder);
+                assert_eq!(result.collapse(), (a + b) & 0xFFFFFFFF);
+
+                let result = FInt8::from(a).add_with(FInt8::from(b), adder);
+                assert_eq!(result.collapse(), (a + b) & 0xFF);
+            }
+        }
+    }
+
+    #[test]
+    fn test_adders_fuzzy() {
+        // Fresh operands for each adder, so that no carry gets deduplicated into another adder's
+        let probabilities = |adder| {
+            let a = FInt8::build(|_| FBit::from_float(0.3));
+            let b = FInt8::build(|_| FBit::from_float(0.7));
+            let result = a.add_with(b, adder);
+            (0..8).map(|i| result[i].probability()).collect::<Vec<_>>()
+        };
+
+        let ripple = probabilities(Adder::RippleCarry);
+        let lookahead = probabilities(Adder::CarryLookahead);
+        let kogge_stone = probabilities(Adder::KoggeStone);
+        let select = probabilities(Adder::CarrySelect);
+
+        // Ripple carry and carry select only ever combine independent bits, so they are exact
+        assert!((ripple[2] - 0.52691).abs() < 1e-5);
+        for i in 0..8 {
+            assert!((ripple[i] - select[i]).abs() < 1e-12);
+        }
+
+        // The other two reuse propagate and generate bits, which are taken to be independent
+        assert!((lookahead[2] - ripple[2]).abs() > 1e-3);
+        assert!((kogge_stone[2] - ripple[2]).abs() > 1e-3);
+        assert!((lookahead[4] - kogge_stone[4]).abs() > 1e-4);
+    }
+}
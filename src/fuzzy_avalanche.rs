@@ -0,0 +1,199 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_int::*;
+
+pub struct AvalancheReport {
+    // matrix[i][j] is the probability that output bit j flips given that input bit i flipped,
+    // averaged over the messages
+    pub matrix: Vec<Vec<f64>>,
+    // flips[i][m][j] is the same probability for message m alone
+    flips: Vec<Vec<Vec<f64>>>,
+}
+
+// Taps every input bit of every message in turn with a flip of probability `tap`, and measures
+// how far each output bit moves away from the untapped digest. A tap of 1.0 is the classic
+// avalanche test, which needs many concrete messages, while a fuzzy message averages over its
+// possible values by itself. The messages all have to be the same length
+pub fn avalanche<const N: usize>(
+    hash: impl Fn(&[&FInt8]) -> FInt<N>,
+    messages: &[Vec<&FInt8>],
+    tap: f64,
+) -> AvalancheReport {
+    assert!(0.0 < tap && tap <= 1.0);
+    assert!(!messages.is_empty());
+
+    let length = messages[0].len();
+    assert!(messages.iter().all(|message| message.len() == length));
+
+    let bases = messages
+        .iter()
+        .map(|message| hash(message.as_slice()))
+        .collect::<Vec<_>>();
+
+    // Near 1 the probabilities themselves round together, so the move is read off whichever
+    // side of the base is the small one
+    let flip = |output: &FBit, base: &FBit| {
+        if base.probability() > 0.5 {
+            (output.complement_probability() - base.complement_probability()).abs()
+        } else {
+            (output.probability() - base.probability()).abs()
+        }
+    };
+
+    let flips = (0..8 * length)
+        .map(|input| {
+            messages
+                .iter()
+                .zip(&bases)
+                .map(|(message, base)| {
+                    let tapped = message
+                        .iter()
+                        .enumerate()
+                        .map(|(j, byte)| {
+                            if j == input / 8 {
+                                FInt8::build(|i| {
+                                    if i == input % 8 {
+                                        &byte[i] ^ FBit::from_float(tap)
+                                    } else {
+                                        &byte[i]
+                                    }
+                                })
+                            } else {
+                                *byte
+                            }
+                        })
+                        .collect::<Vec<&FInt8>>();
+
+                    let output = hash(tapped.as_slice());
+
+                    (0..N).map(|j| flip(&output[j], &base[j]) / tap).collect()
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<Vec<f64>>>>();
+
+    let matrix = flips
+        .iter()
+        .map(|per_message: &Vec<Vec<f64>>| {
+            (0..N)
+                .map(|j| {
+                    per_message.iter().map(|row| row[j]).sum::<f64>() / per_message.len() as f64
+                })
+                .collect()
+        })
+        .collect();
+
+    AvalancheReport { matrix, flips }
+}
+
+impl AvalancheReport {
+    fn sac_deviations(&self) -> impl Iterator<Item = f64> + '_ {
+        self.matrix
+            .iter()
+            .flat_map(|row| row.iter().map(|p| (p - 0.5).abs()))
+    }
+
+    // Strict avalanche criterion: every output bit should flip half of the time
+    pub fn sac_mean_deviation(&self) -> f64 {
+        let count = self.matrix.iter().map(|row| row.len()).sum::<usize>();
+        self.sac_deviations().sum::<f64>() / count as f64
+    }
+
+    pub fn sac_max_deviation(&self) -> f64 {
+        self.sac_deviations().fold(0.0, f64::max)
+    }
+
+    // Bit independence criterion: for a fixed input bit, the flips of any two output bits
+    // should be uncorrelated across the messages. Pairs where either output flips the same way
+    // for every message are left out
+    pub fn bic_max_correlation(&self) -> f64 {
+        let mut result: f64 = 0.0;
+
+        for per_message in &self.flips {
+            let samples = per_message.len() as f64;
+            let outputs = per_message.first().map_or(0, |row| row.len());
+
+            let column = |j: usize| per_message.iter().map(move |row| row[j]);
+
+            let means = (0..outputs)
+                .map(|j| column(j).sum::<f64>() / samples)
+                .collect::<Vec<_>>();
+            let deviations = (0..outputs)
+                .map(|j| {
+                    column(j)
+                        .map(|p| (p - means[j]).powi(2))
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .collect::<Vec<_>>();
+
+            for a in 0..outputs {
+                for b in a + 1..outputs {
+                    if deviations[a] == 0.0 || deviations[b] == 0.0 {
+                        continue;
+                    }
+
+                    let covariance = column(a)
+                        .zip(column(b))
+                        .map(|(pa, pb)| (pa - means[a]) * (pb - means[b]))
+                        .sum::<f64>();
+
+                    result = result.max((covariance / (deviations[a] * deviations[b])).abs());
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_hash::FuzzyHash;
+    use crate::fuzzy_sha1::FuzzySha1;
+
+    fn messages(values: &[usize]) -> Vec<Vec<&FInt8>> {
+        values.iter().map(|value| vec![FInt8::from(*value)]).collect()
+    }
+
+    #[test]
+    fn test_avalanche_sha1() {
+        let messages = messages(&[0x00, 0x61, 0x7f, 0x80, 0xa5, 0xff]);
+
+        let report = avalanche(FuzzySha1::digest, &messages, 1.0);
+
+        assert_eq!(report.matrix.len(), 8);
+        assert!(report.matrix.iter().all(|row| row.len() == 160));
+        assert!(report.sac_mean_deviation() < 0.25);
+
+        // A single round leaves most of the state untouched
+        let weak = avalanche(
+            |message| {
+                let mut hasher = FuzzySha1::with_rounds(1);
+                hasher.update(message);
+                hasher.finalize()
+            },
+            &messages,
+            1.0,
+        );
+
+        assert!(weak.sac_mean_deviation() > 0.45);
+        assert_eq!(weak.sac_max_deviation(), 0.5);
+    }
+
+    #[test]
+    fn test_avalanche_bic() {
+        let messages = messages(&[0x03, 0x15, 0x2a, 0x47, 0x9c, 0xe1]);
+
+        // Both halves of the output are the same function, so their flips are fully correlated
+        let duplicated = |message: &[&FInt8]| {
+            let square = message[0] * message[0];
+            FInt8::combine(&[square, square])
+        };
+
+        let report = avalanche(duplicated, &messages, 1.0);
+
+        assert_eq!(report.matrix[0].len(), 16);
+        assert!((report.bic_max_correlation() - 1.0).abs() < 1e-12);
+    }
+}
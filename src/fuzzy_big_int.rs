@@ -0,0 +1,431 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_bit_slice;
+use crate::fuzzy_int::FInt;
+
+use std::fmt;
+use std::ops::Index;
+use std::ops::{Add, Mul, Rem, Shl, Sub};
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+#[derive(Clone)]
+pub struct FBigInt {
+    bits: Vec<FBit>,
+}
+
+impl FBigInt {
+    pub fn init(len: usize) -> FBigInt {
+        FBigInt {
+            bits: vec![FBit::FALSE; len],
+        }
+    }
+
+    pub fn from_bits(bits: Vec<FBit>) -> FBigInt {
+        FBigInt { bits }
+    }
+
+    pub fn build<'a>(len: usize, mut builder: impl FnMut(usize) -> &'a FBit) -> &'a FBigInt {
+        let mut result = Self::init(len);
+
+        for (i, bit) in result.bits.iter_mut().enumerate() {
+            *bit = *builder(i);
+        }
+
+        &result
+    }
+
+    pub fn from<'a>(n: usize, len: usize) -> &'a FBigInt {
+        FBigInt::build(len, |i| FBit::from(i < 64 && 0 != (n & ((1 as usize) << i))))
+    }
+
+    pub fn from_hex<'a>(hex: &str, len: usize) -> &'a FBigInt {
+        let digits = hex
+            .chars()
+            .rev()
+            .filter_map(|c| c.to_digit(16))
+            .collect::<Vec<_>>();
+
+        FBigInt::build(len, |i| {
+            FBit::from(0 != digits.get(i / 4).map_or(0, |digit| digit & (1 << (i % 4))))
+        })
+    }
+
+    pub fn from_int<const L: usize>(int: &FInt<L>) -> &FBigInt {
+        FBigInt::build(L, |i| &int[i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn resize(&self, len: usize) -> &FBigInt {
+        FBigInt::build(len, |i| &self[i])
+    }
+
+    pub fn collapse_hex(&self) -> String {
+        let digits = self
+            .bits
+            .chunks(4)
+            .rev()
+            .map(|chunk| {
+                let mut digit = 0;
+                for (i, bit) in chunk.iter().enumerate() {
+                    if bit.collapse() {
+                        digit |= 1 << i;
+                    }
+                }
+                format!("{:x}", digit)
+            })
+            .collect::<String>();
+
+        match digits.trim_start_matches('0') {
+            "" => String::from("0"),
+            trimmed => String::from(trimmed),
+        }
+    }
+}
+
+impl FBigInt {
+    pub fn carrying_add(&self, rhs: &Self, carry: &FBit) -> (&Self, &FBit) {
+        let mut carry = carry.clone();
+        let len = self.len().max(rhs.len());
+        let result = FBigInt::build(len, |i| FBit::add_carry(&self[i], &rhs[i], &mut carry));
+        (result, &carry)
+    }
+
+    pub fn overflowing_sub(&self, rhs: &Self) -> (&Self, &FBit) {
+        let len = self.len().max(rhs.len());
+        let (result, carry) = self.carrying_add(!rhs.resize(len), FBit::from(true));
+        (result, !carry)
+    }
+
+    pub fn lt(&self, rhs: &Self) -> &FBit {
+        self.overflowing_sub(rhs).1
+    }
+
+    pub fn ge(&self, rhs: &Self) -> &FBit {
+        !self.lt(rhs)
+    }
+
+    pub fn eq(&self, rhs: &Self) -> &FBit {
+        let mut result = FBit::TRUE;
+
+        for i in 0..self.len().max(rhs.len()) {
+            result = *(&result & !(&self[i] ^ &rhs[i]));
+        }
+
+        &result
+    }
+
+    // The quotient is as wide as the dividend and the remainder is as wide as the divisor
+    pub fn div_rem(&self, rhs: &Self) -> (&Self, &Self, &FBit) {
+        let (quotient, remainder) = fuzzy_bit_slice::div_rem(&self.bits, &rhs.bits);
+
+        (
+            &FBigInt::from_bits(quotient),
+            &FBigInt::from_bits(remainder),
+            rhs.eq(FBigInt::from(0, rhs.len())),
+        )
+    }
+
+    // Truncated to the width of self, like the product of two FInts
+    pub fn wrapping_mul(&self, rhs: &Self) -> &Self {
+        let mut result = FBigInt::init(self.len());
+
+        for i in 0..rhs.len().min(self.len()) {
+            result = *(&result + (self << i) * &rhs[i]);
+        }
+
+        &result
+    }
+
+    // Operands are expected to already be reduced below the modulus
+    pub fn mod_add(&self, rhs: &Self, modulus: &Self) -> &Self {
+        let (sum, carry) = self.carrying_add(rhs, FBit::from(false));
+        let (reduced, borrow) = sum.overflowing_sub(modulus);
+        (carry | !borrow).piecewise_big_int(reduced, sum)
+    }
+
+    // Squares and multiplies in Montgomery form when the modulus is known to be odd, as it is
+    // for RSA, so every step is a few multiplications rather than a full division. Any other
+    // modulus is reduced with a division after every step
+    pub fn mod_pow(&self, exponent: &Self, modulus: &Self) -> &FBigInt {
+        if modulus[0] == FBit::TRUE {
+            return FBigMontgomery::new(modulus).pow(self % modulus, exponent);
+        }
+
+        let len = modulus.len();
+        let base = self % modulus;
+        let mut result = FBigInt::from(1, len) % modulus;
+
+        for i in (0..exponent.len()).rev() {
+            let squared = (result * result) % modulus;
+            let multiplied = (squared * base) % modulus;
+            result = exponent[i].piecewise_big_int(multiplied, squared);
+        }
+
+        result
+    }
+}
+
+impl Index<usize> for FBigInt {
+    type Output = FBit;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.bits.get(index).unwrap_or_default()
+    }
+}
+
+impl Add for &FBigInt {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.carrying_add(rhs, FBit::from(false)).0
+    }
+}
+
+impl Sub for &FBigInt {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.overflowing_sub(rhs).0
+    }
+}
+
+impl Shl<usize> for &FBigInt {
+    type Output = Self;
+
+    fn shl(self, shift: usize) -> Self::Output {
+        FBigInt::build(self.len(), |i| &self[i.wrapping_sub(shift)])
+    }
+}
+
+impl Mul<&FBit> for &FBigInt {
+    type Output = Self;
+
+    fn mul(self, rhs: &FBit) -> Self::Output {
+        FBigInt::build(self.len(), |i| &self[i] & rhs)
+    }
+}
+
+impl Mul for &FBigInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let len = self.len() + rhs.len();
+        let wide = self.resize(len);
+        let mut result = FBigInt::init(len);
+
+        for i in 0..rhs.len() {
+            result = *(&result + (wide << i) * &rhs[i]);
+        }
+
+        &result
+    }
+}
+
+impl Rem for &FBigInt {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Not for &FBigInt {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        FBigInt::build(self.len(), |i| !&self[i])
+    }
+}
+
+impl BitAnd for &FBigInt {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        FBigInt::build(self.len().max(rhs.len()), |i| &self[i] & &rhs[i])
+    }
+}
+
+impl BitOr for &FBigInt {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        FBigInt::build(self.len().max(rhs.len()), |i| &self[i] | &rhs[i])
+    }
+}
+
+impl BitXor for &FBigInt {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        FBigInt::build(self.len().max(rhs.len()), |i| &self[i] ^ &rhs[i])
+    }
+}
+
+impl fmt::Debug for FBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fuzzy_bit_slice::fmt_bits(f, &format!("FBigInt{}", self.len()), &self.bits)
+    }
+}
+
+// Montgomery multiplication for FBigInts as wide as the modulus, which has to be odd. Products
+// are reduced with a few multiplications instead of a division
+#[derive(Clone)]
+pub struct FBigMontgomery {
+    modulus: FBigInt,
+    inverse: FBigInt,
+    r_squared: FBigInt,
+}
+
+impl FBigMontgomery {
+    pub fn new(modulus: &FBigInt) -> FBigMontgomery {
+        assert!(modulus[0] == FBit::TRUE, "Montgomery reduction needs an odd modulus");
+
+        let len = modulus.len();
+
+        let mut inverse = modulus.clone();
+        let mut precision = 3;
+
+        while precision < len {
+            let product = modulus.wrapping_mul(&inverse);
+            inverse = *inverse.wrapping_mul(FBigInt::from(2, len) - product);
+            precision *= 2;
+        }
+
+        let mut r_squared = *FBigInt::from(1, len);
+
+        for _ in 0..2 * len {
+            r_squared = *r_squared.mod_add(&r_squared, modulus);
+        }
+
+        FBigMontgomery {
+            modulus: modulus.clone(),
+            inverse: *(FBigInt::from(0, len) - &inverse),
+            r_squared,
+        }
+    }
+
+    // Reduces a double width product
+    pub fn reduce(&self, product: &FBigInt) -> &FBigInt {
+        let len = self.modulus.len();
+        let low = product.resize(len);
+        let high = FBigInt::build(len, |i| &product[len + i]);
+
+        let m = low.wrapping_mul(&self.inverse);
+        let m_modulus = m * &self.modulus;
+        let m_high = FBigInt::build(len, |i| &m_modulus[len + i]);
+
+        let (_, carry) = low.carrying_add(m_modulus.resize(len), FBit::from(false));
+        let (t, overflow) = high.carrying_add(m_high, carry);
+
+        let (reduced, borrow) = t.overflowing_sub(&self.modulus);
+        (overflow | !borrow).piecewise_big_int(reduced, t)
+    }
+
+    pub fn to_montgomery(&self, value: &FBigInt) -> &FBigInt {
+        self.reduce(value.resize(self.modulus.len()) * &self.r_squared)
+    }
+
+    pub fn from_montgomery(&self, value: &FBigInt) -> &FBigInt {
+        self.reduce(value)
+    }
+
+    pub fn mul(&self, lhs: &FBigInt, rhs: &FBigInt) -> &FBigInt {
+        self.reduce(lhs * rhs)
+    }
+
+    pub fn pow(&self, base: &FBigInt, exponent: &FBigInt) -> &FBigInt {
+        let base = self.to_montgomery(base);
+        let mut result = self.to_montgomery(FBigInt::from(1, self.modulus.len()));
+
+        for i in (0..exponent.len()).rev() {
+            let squared = self.mul(result, result);
+            result = exponent[i].piecewise_big_int(self.mul(squared, base), squared);
+        }
+
+        self.from_montgomery(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fbigint_arithmetic() {
+        let a = FBigInt::from_hex("FEDCBA9876543210FEDCBA9876543210", 128);
+        let b = FBigInt::from_hex("0123456789ABCDEF0123456789ABCDEF", 128);
+        let m = FBigInt::from_hex("FFFFFFFFFFFFFFC5", 64);
+
+        assert_eq!((a + b).collapse_hex(), "ffffffffffffffffffffffffffffffff");
+        assert_eq!((a - b).collapse_hex(), "fdb97530eca86421fdb97530eca86421");
+        assert_eq!(
+            (a * b).collapse_hex(),
+            "121fa00ad77d742247acc9140513b74458fab20783af1222236d88fe5618cf0"
+        );
+        assert_eq!((a % m).collapse_hex(), "bbbbbbbbbbbbc959");
+    }
+
+    #[test]
+    fn test_fbigint_mod_pow() {
+        let base = FBigInt::from(4, 16);
+        let exponent = FBigInt::from(13, 8);
+        let modulus = FBigInt::from(497, 16);
+
+        assert_eq!(base.mod_pow(exponent, modulus).collapse_hex(), "1bd");
+
+        let base = FBigInt::from_hex("123456789ABCDEF", 64);
+        let exponent = FBigInt::from(0x10001, 17);
+        let modulus = FBigInt::from_hex("1FFFFFFFFFFFFFFF", 64);
+
+        assert_eq!(
+            base.mod_pow(exponent, modulus).collapse_hex(),
+            "1637317610041a2f"
+        );
+    }
+
+    #[test]
+    fn test_fbigint_mod_pow_even() {
+        let base = FBigInt::from(4, 16);
+        let exponent = FBigInt::from(13, 8);
+        let modulus = FBigInt::from(498, 16);
+
+        assert_eq!(base.mod_pow(exponent, modulus).collapse_hex(), "178");
+
+        let base = FBigInt::from_hex("123456789ABCDEF", 64);
+        let exponent = FBigInt::from(0x10001, 17);
+        let modulus = FBigInt::from_hex("1FFFFFFFFFFFFFFE", 64);
+
+        assert_eq!(
+            base.mod_pow(exponent, modulus).collapse_hex(),
+            "3456789abcdf011"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fbigint_montgomery_even() {
+        FBigMontgomery::new(FBigInt::from(498, 16));
+    }
+
+    #[test]
+    fn test_fbigint_montgomery() {
+        let q = FBigInt::from(0x7FFFFFFF, 32);
+        let a = FBigInt::from(0x12345678, 32);
+        let b = FBigInt::from(0x7654321, 32);
+
+        let montgomery = FBigMontgomery::new(q);
+
+        let product = montgomery.mul(montgomery.to_montgomery(a), montgomery.to_montgomery(b));
+        assert_eq!(montgomery.from_montgomery(product).collapse_hex(), "71c5d10a");
+        assert_eq!(
+            montgomery.pow(a, FBigInt::from(0x10001, 17)).collapse_hex(),
+            "1d07591e"
+        );
+    }
+}
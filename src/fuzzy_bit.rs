@@ -2,41 +2,148 @@ use std::fmt;
 use std::ops::{BitAnd, BitOr, BitXor, Not};
 use std::hash::{Hash, Hasher};
 
+use std::borrow::Cow;
 use std::default::Default;
 use std::sync::Arc;
 
 use crate::fuzzy_bit_set::deduplicate_fbit;
+use crate::fuzzy_bit_anf::FBitAnf;
 use crate::fuzzy_bit_hash::FBitHash;
+use crate::fuzzy_bit_interval::FBitInterval;
+use crate::fuzzy_bit_linear::FBitLinear;
+use crate::fuzzy_big_int::FBigInt;
 use crate::fuzzy_int::FInt;
+use crate::fuzzy_probability::Probability;
 
+#[cfg(any(
+    all(feature = "f32", any(feature = "rational", feature = "dyadic", feature = "log_odds")),
+    all(feature = "rational", any(feature = "dyadic", feature = "log_odds")),
+    all(feature = "dyadic", feature = "log_odds")
+))]
+compile_error!("only one of the f32, rational, dyadic and log_odds features can be enabled");
+
+#[cfg(feature = "f32")]
+pub type RepT = f32;
+
+#[cfg(feature = "rational")]
+pub type RepT = crate::fuzzy_probability::Rational;
+
+#[cfg(feature = "dyadic")]
+pub type RepT = crate::fuzzy_probability::Dyadic;
+
+#[cfg(feature = "log_odds")]
+pub type RepT = crate::fuzzy_probability::LogOdds;
+
+#[cfg(not(any(
+    feature = "f32",
+    feature = "rational",
+    feature = "dyadic",
+    feature = "log_odds"
+)))]
 pub type RepT = f64;
 
+// Besides the probability, a bit can carry the XOR-difference to a second execution and a
+// linear approximation. Each is only carried by bits computed from an input that was seeded
+// with it, and inputs without it stand in with their default, so a plain evaluation pays
+// nothing for them. The interval is the exception: a bound on a derived bit cannot be recovered
+// from its probability, which assumes independent inputs, so every bit carries its own
 #[derive(Clone)]
 pub struct FBit {
     p: RepT,
+    d: Option<f64>,
+    interval: FBitInterval,
+    linear: Option<Arc<FBitLinear>>,
+    anf: Option<Arc<FBitAnf>>,
     hash: Arc<FBitHash>,
 }
 
 impl FBit {
-    const TRUE_VALUE: RepT = 1.0;
-    const FALSE_VALUE: RepT = 0.0;
+    const TRUE_VALUE: RepT = RepT::ONE;
+    const FALSE_VALUE: RepT = RepT::ZERO;
 
-    pub const TRUE: FBit = FBit::from_rep(Self::TRUE_VALUE, FBitHash::TRUE);
-    pub const FALSE: FBit = FBit::from_rep(Self::FALSE_VALUE, FBitHash::FALSE);
+    pub const TRUE: FBit = FBit::from_constant(true, FBitHash::TRUE);
+    pub const FALSE: FBit = FBit::from_constant(false, FBitHash::FALSE);
 }
 
 impl FBit {
-    const fn from_rep(p: RepT, hash: FBitHash) -> FBit {
+    const fn from_constant(b: bool, hash: FBitHash) -> FBit {
+        let p = if b { 1.0 } else { 0.0 };
+
+        FBit {
+            p: if b { Self::TRUE_VALUE } else { Self::FALSE_VALUE },
+            d: None,
+            interval: FBitInterval::point(p),
+            linear: None,
+            anf: Some(Arc::new(FBitAnf::constant(b))),
+            hash: Arc::new(hash),
+        }
+    }
+
+    fn from_rep(p: RepT, hash: FBitHash) -> FBit {
+        if p == Self::TRUE_VALUE || p == Self::FALSE_VALUE {
+            return FBit::from_constant(p == Self::TRUE_VALUE, hash);
+        }
+
+        let interval = FBitInterval::point(p.to_f64());
+
         FBit {
             p,
+            d: None,
+            interval,
+            linear: None,
+            anf: None,
             hash: Arc::new(hash),
         }
     }
 
     pub fn from_float<'a>(p: f64) -> &'a FBit {
+        &FBit::from_rep(RepT::from_f64(p), FBitHash::new())
+    }
+
+    // For probabilities that a float cannot hold exactly, such as Rational::new(1, 3)
+    pub fn from_probability<'a>(p: RepT) -> &'a FBit {
         &FBit::from_rep(p, FBitHash::new())
     }
 
+    // A bit with probability p of being set, and probability d of differing between two executions
+    pub fn from_difference<'a>(p: f64, d: f64) -> &'a FBit {
+        &FBit {
+            d: Some(d),
+            ..FBit::from_rep(RepT::from_f64(p), FBitHash::new())
+        }
+    }
+
+    // A bit whose probability is only known to lie in [lo, hi]
+    pub fn from_interval<'a>(lo: f64, hi: f64) -> &'a FBit {
+        &FBit {
+            interval: FBitInterval::new(lo, hi),
+            ..FBit::from_rep(RepT::from_f64((lo + hi) / 2.0), FBitHash::new())
+        }
+    }
+
+    // A named input variable, which linear approximations are expressed in terms of
+    pub fn from_variable<'a>(p: f64, id: usize) -> &'a FBit {
+        &FBit {
+            linear: Some(Arc::new(FBitLinear::variable(id))),
+            ..FBit::from_rep(RepT::from_f64(p), FBitHash::new())
+        }
+    }
+
+    // A named input variable that everything computed from it keeps the algebraic normal form
+    // of. The number of monomials can grow exponentially with the degree, so monomials above
+    // FBitAnf::DEFAULT_MAX_DEGREE are dropped
+    pub fn from_anf_variable<'a>(p: f64, id: usize) -> &'a FBit {
+        FBit::from_truncated_anf_variable(p, id, FBitAnf::DEFAULT_MAX_DEGREE)
+    }
+
+    // As above, but monomials are dropped above max_degree, which may be usize::MAX
+    pub fn from_truncated_anf_variable<'a>(p: f64, id: usize, max_degree: usize) -> &'a FBit {
+        &FBit {
+            anf: Some(Arc::new(FBitAnf::variable(id, max_degree))),
+            ..FBit::from_rep(RepT::from_f64(p), FBitHash::new())
+        }
+    }
+
     pub const fn from(b: bool) -> &'static FBit {
         if b {
             &Self::TRUE
@@ -46,7 +153,130 @@ impl FBit {
     }
 
     pub fn collapse(&self) -> bool {
-        0.5 < self.p
+        FBit::complement(&self.p) < self.p
+    }
+
+    // The analyses built on top of the probability all work in f64, whatever RepT is. A
+    // probability within 1e-16 of 1 rounds to 1, so they read the chance of the bit being unset
+    // from complement_probability, and work with logarithms where the values get small
+    pub fn probability(&self) -> f64 {
+        self.p.to_f64()
+    }
+
+    pub fn complement_probability(&self) -> f64 {
+        self.p.complement_to_f64()
+    }
+
+    pub fn ln_probability(&self) -> f64 {
+        self.p.ln()
+    }
+
+    pub fn ln_complement_probability(&self) -> f64 {
+        self.p.ln_complement()
+    }
+
+    pub fn probability_rep(&self) -> RepT {
+        self.p.clone()
+    }
+
+    pub fn log_odds(&self) -> f64 {
+        self.p.log_odds()
+    }
+
+    fn complement(p: &RepT) -> RepT {
+        &Self::TRUE_VALUE - p
+    }
+
+    pub fn difference(&self) -> f64 {
+        self.d.unwrap_or(0.0)
+    }
+
+    pub fn interval(&self) -> FBitInterval {
+        self.interval
+    }
+
+    pub fn linear(&self) -> Cow<'_, FBitLinear> {
+        match &self.linear {
+            Some(linear) => Cow::Borrowed(linear),
+            None => Cow::Owned(FBitLinear::constant(1.0 - 2.0 * self.probability())),
+        }
+    }
+
+    // None when the bit depends on anything other than constants and named variables
+    pub fn anf(&self) -> Option<&FBitAnf> {
+        self.anf.as_deref()
+    }
+
+    pub fn is_complement(&self, other: &FBit) -> bool {
+        !*self.hash == *other.hash
+    }
+
+    // Bits are identified by their hash, which ignores the analyses they carry. Deduplication
+    // only hands out an earlier bit in place of a new one when these agree as well
+    pub(crate) fn same_analyses(&self, other: &FBit) -> bool {
+        self.d == other.d
+            && self.interval == other.interval
+            && self.linear == other.linear
+            && self.anf == other.anf
+    }
+}
+
+impl FBit {
+    pub fn entropy(&self) -> f64 {
+        let term = |p: f64, ln_p: f64| if p <= 0.0 { 0.0 } else { -p * ln_p };
+        let nats = term(self.probability(), self.ln_probability())
+            + term(self.complement_probability(), self.ln_complement_probability());
+
+        nats / std::f64::consts::LN_2
+    }
+
+    pub fn min_entropy(&self) -> f64 {
+        -self.ln_probability().max(self.ln_complement_probability()) / std::f64::consts::LN_2
+    }
+}
+
+// The difference of each bit is tracked as if the value and the difference were independent.
+// For a gate output the difference is P(out) + P(out') - 2 P(out and out'), where out' is the
+// value in the second execution
+impl FBit {
+    fn primed(&self) -> f64 {
+        let (p, d) = (self.probability(), self.difference());
+        p * (1.0 - d) + self.complement_probability() * d
+    }
+
+    fn both(&self) -> f64 {
+        self.probability() * (1.0 - self.difference())
+    }
+
+    fn neither(&self) -> f64 {
+        self.complement_probability() * (1.0 - self.difference())
+    }
+
+    fn differs(p: f64, primed: f64, both: f64) -> f64 {
+        p + primed - 2.0 * both
+    }
+}
+
+// The difference and linear approximation of a gate output are computed only if one of the
+// inputs carries them
+impl FBit {
+    fn seeded<T>(
+        inputs: &[&FBit],
+        track: impl Fn(&FBit) -> bool,
+        compute: impl FnOnce() -> T,
+    ) -> Option<T> {
+        inputs.iter().any(|bit| track(bit)).then(compute)
+    }
+
+    fn combine_anf(
+        lhs: &FBit,
+        rhs: &FBit,
+        func: impl Fn(&FBitAnf, &FBitAnf) -> FBitAnf,
+    ) -> Option<Arc<FBitAnf>> {
+        match (&lhs.anf, &rhs.anf) {
+            (Some(l), Some(r)) => Some(Arc::new(func(l, r))),
+            _ => None,
+        }
     }
 }
 
@@ -81,7 +311,11 @@ impl Not for &FBit {
 
     fn not(self) -> Self::Output {
         deduplicate_fbit(&FBit {
-            p: FBit::TRUE_VALUE - self.p,
+            p: FBit::complement(&self.p),
+            d: self.d,
+            interval: self.interval.not(),
+            linear: self.linear.as_ref().map(|linear| Arc::new(linear.not())),
+            anf: self.anf.as_ref().map(|anf| Arc::new(anf.not())),
             hash: Arc::from(! *self.hash),
         })
     }
@@ -91,8 +325,26 @@ impl BitAnd for &FBit {
     type Output = Self;
 
     fn bitand(self, rhs: Self) -> Self::Output {
+        let inputs = [self, rhs];
+
         deduplicate_fbit(&FBit {
-            p: self.p * rhs.p,
+            p: &self.p * &rhs.p,
+            d: FBit::seeded(&inputs, |bit| bit.d.is_some(), || {
+                FBit::differs(
+                    self.probability() * rhs.probability(),
+                    self.primed() * rhs.primed(),
+                    self.both() * rhs.both(),
+                )
+            }),
+            interval: self.interval.and(&rhs.interval),
+            linear: FBit::seeded(&inputs, |bit| bit.linear.is_some(), || {
+                Arc::new(self.linear().and(
+                    self.probability(),
+                    &rhs.linear(),
+                    rhs.probability(),
+                ))
+            }),
+            anf: FBit::combine_anf(self, rhs, FBitAnf::and),
             hash: Arc::from(*self.hash & *rhs.hash),
         })
     }
@@ -102,8 +354,26 @@ impl BitOr for &FBit {
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
+        let inputs = [self, rhs];
+
         deduplicate_fbit(&FBit {
-            p: self.p + rhs.p - 1.0 * (self.p * rhs.p),
+            p: FBit::complement(&(&FBit::complement(&self.p) * &FBit::complement(&rhs.p))),
+            d: FBit::seeded(&inputs, |bit| bit.d.is_some(), || {
+                let (a, b) = (self.probability(), rhs.probability());
+                let p = a + b - a * b;
+                let primed = self.primed() + rhs.primed() - self.primed() * rhs.primed();
+                FBit::differs(p, primed, p + primed - 1.0 + self.neither() * rhs.neither())
+            }),
+            interval: self.interval.or(&rhs.interval),
+            linear: FBit::seeded(&inputs, |bit| bit.linear.is_some(), || {
+                Arc::new(
+                    self.linear()
+                        .not()
+                        .and(1.0 - self.probability(), &rhs.linear().not(), 1.0 - rhs.probability())
+                        .not(),
+                )
+            }),
+            anf: FBit::combine_anf(self, rhs, FBitAnf::or),
             hash: Arc::from(*self.hash | *rhs.hash),
         })
     }
@@ -113,18 +383,58 @@ impl BitXor for &FBit {
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
+        let inputs = [self, rhs];
+
         deduplicate_fbit(&FBit {
-            p: self.p + rhs.p - 2.0 * (self.p * rhs.p),
-            hash: Arc::from(*self.hash | *rhs.hash),
+            p: &(&self.p * &FBit::complement(&rhs.p)) + &(&FBit::complement(&self.p) * &rhs.p),
+            d: FBit::seeded(&inputs, |bit| bit.d.is_some(), || {
+                let (a, b) = (self.difference(), rhs.difference());
+                a + b - 2.0 * (a * b)
+            }),
+            interval: self.interval.xor(&rhs.interval),
+            linear: FBit::seeded(&inputs, |bit| bit.linear.is_some(), || {
+                Arc::new(self.linear().xor(&rhs.linear()))
+            }),
+            anf: FBit::combine_anf(self, rhs, FBitAnf::xor),
+            hash: Arc::from(*self.hash ^ *rhs.hash),
         })
     }
 }
 
 impl FBit {
     pub fn piecewise<'a>(&'a self, true_value: &'a FBit, false_value: &'a FBit) -> &'a FBit {
+        let (t, f) = (true_value, false_value);
+        let inputs = [self, t, f];
+
         deduplicate_fbit(&FBit {
-            p: (self.p) * true_value.p + (1.0 - self.p) * false_value.p,
-            hash: Arc::from((*self.hash & *true_value.hash) | ((! *self.hash) & *false_value.hash)),
+            p: &(&self.p * &t.p) + &(&FBit::complement(&self.p) * &f.p),
+            d: FBit::seeded(&inputs, |bit| bit.d.is_some(), || {
+                let (s_p, t_p, f_p) = (self.probability(), t.probability(), f.probability());
+                let s_d = self.difference();
+                let p = s_p * t_p + (1.0 - s_p) * f_p;
+                let primed = self.primed() * t.primed() + (1.0 - self.primed()) * f.primed();
+
+                // Split on whether the selector took the same branch in both executions
+                let both = self.both() * t.both()
+                    + s_p * s_d * t_p * f.primed()
+                    + (1.0 - s_p) * s_d * f_p * t.primed()
+                    + self.neither() * f.both();
+
+                FBit::differs(p, primed, both)
+            }),
+            interval: self.interval.piecewise(&t.interval, &f.interval),
+            linear: FBit::seeded(&inputs, |bit| bit.linear.is_some(), || {
+                // s ? t : f is the same as f ^ (s & (t ^ f))
+                let (t_p, f_p) = (t.probability(), f.probability());
+                let tf = t.linear().xor(&f.linear());
+                let tf_p = t_p + f_p - 2.0 * t_p * f_p;
+                Arc::new(self.linear().and(self.probability(), &tf, tf_p).xor(&f.linear()))
+            }),
+            anf: match (&self.anf, &t.anf, &f.anf) {
+                (Some(s), Some(t), Some(f)) => Some(Arc::new(s.piecewise(t, f))),
+                _ => None,
+            },
+            hash: Arc::from((*self.hash & *t.hash) | ((! *self.hash) & *f.hash)),
         })
     }
 
@@ -135,6 +445,15 @@ impl FBit {
     ) -> &'a FInt<L> {
         &FInt::build(|i| self.piecewise(&true_value[i], &false_value[i]))
     }
+
+    pub fn piecewise_big_int<'a>(
+        &'a self,
+        true_value: &'a FBigInt,
+        false_value: &'a FBigInt,
+    ) -> &'a FBigInt {
+        let len = true_value.len().max(false_value.len());
+        FBigInt::build(len, |i| self.piecewise(&true_value[i], &false_value[i]))
+    }
 }
 
 impl FBit {
@@ -150,3 +469,157 @@ impl fmt::Debug for FBit {
         write!(f, "({})", self.p)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_int::FInt8;
+
+    #[test]
+    fn test_fbit_xor_hash() {
+        let x = FBit::from_float(0.25);
+        let y = FBit::from_float(0.5);
+
+        // XOR with one is the complement, not the constant that OR-ing the hashes would give
+        assert_eq!(x ^ FBit::from(true), !x);
+        assert_eq!((x ^ FBit::from(true)).probability(), 0.75);
+        assert_eq!(x ^ FBit::from(false), x);
+        assert_eq!(x ^ x, FBit::from(false));
+        assert_eq!(x ^ y, y ^ x);
+        assert_ne!(x ^ y, x | y);
+    }
+
+    #[test]
+    fn test_fbit_deduplicate_analyses() {
+        let x = FBit::from_difference(0.5, 0.5);
+
+        // x & x is x, but its difference is worked out as if the inputs were independent
+        let y = x & x;
+        assert_eq!(y, x);
+        assert_eq!(x.difference(), 0.5);
+        assert_eq!(y.difference(), 0.375);
+        assert!(!y.same_analyses(x));
+    }
+
+    #[test]
+    fn test_fbit_difference() {
+        let a = FBit::from_difference(0.5, 1.0);
+        let b = FBit::from_difference(0.5, 0.5);
+        let c = FBit::from_float(0.25);
+
+        assert_eq!((!a).difference(), 1.0);
+        assert_eq!((a ^ b).difference(), 0.5);
+        assert_eq!((a ^ c).difference(), 1.0);
+        assert_eq!((a & c).difference(), 0.25);
+        assert_eq!((a | c).difference(), 0.75);
+        assert_eq!(c.piecewise(a, FBit::from(false)).difference(), 0.25);
+        assert_eq!(a.piecewise(FBit::from(true), FBit::from(false)).difference(), 1.0);
+        assert_eq!((c & FBit::from(true)).difference(), 0.0);
+    }
+
+    #[test]
+    fn test_fbit_linear() {
+        let x = FBit::from_variable(0.5, 0);
+        let y = FBit::from_variable(0.5, 1);
+
+        assert_eq!((x ^ y).linear().mask(), &[0, 1]);
+        assert_eq!((x ^ y).linear().correlation(), 1.0);
+        assert_eq!((!x).linear().correlation(), -1.0);
+        assert_eq!((x & y).linear().correlation().abs(), 0.5);
+        assert_eq!((x | y).linear().correlation().abs(), 0.5);
+
+        let z = FBit::from_variable(0.9, 2);
+
+        assert_eq!((x & z).linear().mask(), &[0]);
+        assert!(((x & z).linear().correlation() - 0.9).abs() < 1e-12);
+        assert_eq!(FBit::from(true).linear().correlation(), -1.0);
+        assert_eq!(x.piecewise(y, y).linear().mask(), &[1]);
+    }
+
+    #[test]
+    fn test_fbit_interval() {
+        let approx_eq = |a: FBitInterval, lo: f64, hi: f64| {
+            (a.lo() - lo).abs() < 1e-12 && (a.hi() - hi).abs() < 1e-12
+        };
+
+        let a = FBit::from_interval(0.2, 0.4);
+        let b = FBit::from_float(0.5);
+
+        assert!(approx_eq((!a).interval(), 0.6, 0.8));
+        assert!(approx_eq((a & b).interval(), 0.0, 0.4));
+        assert!(approx_eq((a | b).interval(), 0.5, 0.9));
+        assert!(approx_eq((a ^ b).interval(), 0.1, 0.9));
+        assert!(approx_eq(b.piecewise(a, !a).interval(), 0.1, 0.9));
+        assert_eq!(FBit::from(true).interval(), FBitInterval::point(1.0));
+
+        // 1 - (1 - 0.1) comes out below 0.1 in floating point, so only outward rounding keeps it
+        let tenth = FBit::from_interval(0.1, 0.1);
+        assert!((!!tenth).interval().contains(0.1));
+        assert!((tenth & FBit::from_interval(1.0, 1.0)).interval().contains(0.1));
+
+        let x = FInt8::build(|i| if i < 4 { FBit::from_interval(0.4, 0.6) } else { FBit::from(false) });
+        let y = FInt8::build(|i| if i < 4 { FBit::from_float(0.3) } else { FBit::from(false) });
+        let sum = x + y;
+
+        for i in 0..8 {
+            let interval = sum[i].interval();
+            assert!(interval.lo() - 1e-12 <= sum[i].probability());
+            assert!(sum[i].probability() <= interval.hi() + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_fbit_unseeded() {
+        let a = FBit::from_float(0.25);
+        let b = FBit::from_float(0.5);
+        let x = FBit::from_variable(0.5, 0);
+
+        for bit in [a & b, a | b, a ^ b, !a, a.piecewise(b, a)] {
+            assert!(bit.d.is_none() && bit.linear.is_none());
+        }
+
+        assert!((a & x).linear.is_some());
+        assert!((a & x).d.is_none());
+        assert_eq!((a & b).linear().correlation(), 0.75);
+
+        // b ^ (b & c) is b & !c, set a quarter of the time. The probability treats b and b & c
+        // as independent and gets this wrong, while the bounds hold either way
+        let z = b ^ (b & FBit::from_float(0.5));
+        assert_eq!(z.probability(), 0.5);
+        assert!(z.interval().contains(0.25));
+    }
+
+    #[test]
+    fn test_fbit_probability_rep() {
+        let quarter = RepT::from_f64(0.25);
+        let a = FBit::from_probability(quarter);
+        let b = FBit::from_float(0.5);
+
+        assert_eq!((a & b).probability_rep(), RepT::from_f64(0.125));
+        assert_eq!((a ^ b).probability_rep(), RepT::from_f64(0.5));
+        assert_eq!((!a).probability_rep(), RepT::from_f64(0.75));
+        assert_eq!(a.piecewise(b, FBit::from(true)).probability(), 0.875);
+        assert!(!a.collapse());
+        assert!((!a).collapse());
+        assert!(b.log_odds().abs() < 1e-12);
+        assert_eq!(a.complement_probability(), 0.75);
+        assert_eq!((!a).complement_probability(), 0.25);
+    }
+
+    #[cfg(any(feature = "log_odds", feature = "rational"))]
+    #[test]
+    fn test_fbit_near_one() {
+        let approx_eq = |a: f64, b: f64| (a - b).abs() <= 1e-9 * b.abs();
+
+        let almost = !FBit::from_probability(RepT::from_f64(1e-30));
+        let x = FInt8::build(|i| if i == 0 { almost } else { FBit::from(false) });
+
+        assert_eq!(almost.probability(), 1.0);
+        assert!(approx_eq(almost.complement_probability(), 1e-30));
+        assert!(approx_eq(almost.min_entropy(), 1e-30 / std::f64::consts::LN_2));
+        assert!(almost.entropy() > 0.0);
+        assert!(approx_eq(x.count_ones_distribution()[0], 1e-30));
+        assert!(approx_eq(x.variance(), 1e-30));
+        assert!(approx_eq(x.most_likely(2)[1].1, 1e-30));
+    }
+}
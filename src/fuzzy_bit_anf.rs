@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_hash::BlockTrace;
+use crate::fuzzy_int::FInt;
+
+type Monomial = Vec<usize>;
+
+// Algebraic normal form of a bit: the constant term plus the XOR of the monomials, each of
+// which is the AND of the sorted input variables it lists. Monomials above max_degree are
+// dropped, after which the polynomial is only exact up to that degree
+#[derive(PartialEq, Clone, Debug)]
+pub struct FBitAnf {
+    constant: bool,
+    monomials: Vec<Monomial>,
+    max_degree: usize,
+    truncated: bool,
+}
+
+impl FBitAnf {
+    pub const DEFAULT_MAX_DEGREE: usize = 4;
+
+    pub const fn constant(constant: bool) -> FBitAnf {
+        FBitAnf {
+            constant,
+            monomials: Vec::new(),
+            max_degree: usize::MAX,
+            truncated: false,
+        }
+    }
+
+    pub fn variable(id: usize, max_degree: usize) -> FBitAnf {
+        assert!(max_degree >= 1);
+
+        FBitAnf {
+            constant: false,
+            monomials: vec![vec![id]],
+            max_degree,
+            truncated: false,
+        }
+    }
+
+    pub fn constant_term(&self) -> bool {
+        self.constant
+    }
+
+    pub fn monomials(&self) -> &[Monomial] {
+        &self.monomials
+    }
+
+    pub fn degree(&self) -> usize {
+        self.monomials.iter().map(|m| m.len()).max().unwrap_or(0)
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn not(&self) -> FBitAnf {
+        FBitAnf {
+            constant: !self.constant,
+            ..self.clone()
+        }
+    }
+
+    pub fn xor(&self, rhs: &FBitAnf) -> FBitAnf {
+        let lhs = self.monomials.iter().collect::<BTreeSet<_>>();
+        let rhs_set = rhs.monomials.iter().collect::<BTreeSet<_>>();
+
+        FBitAnf {
+            constant: self.constant ^ rhs.constant,
+            monomials: lhs.symmetric_difference(&rhs_set).map(|m| (*m).clone()).collect(),
+            max_degree: self.max_degree.min(rhs.max_degree),
+            truncated: self.truncated || rhs.truncated,
+        }
+    }
+
+    // Multiplies out both polynomials, cancelling monomials that appear an even number of times
+    pub fn and(&self, rhs: &FBitAnf) -> FBitAnf {
+        let max_degree = self.max_degree.min(rhs.max_degree);
+        let mut truncated = self.truncated || rhs.truncated;
+        let mut monomials = BTreeSet::new();
+
+        let mut toggle = |monomial: Monomial| {
+            if monomial.len() > max_degree {
+                truncated = true;
+            } else if !monomials.remove(&monomial) {
+                monomials.insert(monomial);
+            }
+        };
+
+        if self.constant {
+            rhs.monomials.iter().for_each(|m| toggle(m.clone()));
+        }
+
+        if rhs.constant {
+            self.monomials.iter().for_each(|m| toggle(m.clone()));
+        }
+
+        for a in &self.monomials {
+            for b in &rhs.monomials {
+                let mut product = a.iter().chain(b).copied().collect::<Monomial>();
+                product.sort_unstable();
+                product.dedup();
+                toggle(product);
+            }
+        }
+
+        FBitAnf {
+            constant: self.constant && rhs.constant,
+            monomials: monomials.into_iter().collect(),
+            max_degree,
+            truncated,
+        }
+    }
+
+    pub fn or(&self, rhs: &FBitAnf) -> FBitAnf {
+        self.xor(rhs).xor(&self.and(rhs))
+    }
+
+    pub fn piecewise(&self, true_value: &FBitAnf, false_value: &FBitAnf) -> FBitAnf {
+        self.and(&true_value.xor(false_value)).xor(false_value)
+    }
+}
+
+impl<const L: usize> FInt<L> {
+    // Algebraic degree of every bit, or None for bits that depend on anything other than
+    // constants and named variables
+    pub fn degrees(&self) -> Vec<Option<usize>> {
+        (0..L).map(|i| self[i].anf().map(FBitAnf::degree)).collect()
+    }
+}
+
+// Degree of every state word bit after each round of a traced compression. The trace ends
+// before the feed-forward, so the degrees of the digest bits come from degrees on the digest
+pub fn round_degrees(trace: &BlockTrace) -> Vec<Vec<Option<usize>>> {
+    trace
+        .iter()
+        .map(|state| state.d.iter().flat_map(|word| word.degrees()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_hash::FuzzyHash;
+    use crate::fuzzy_int::*;
+    use crate::fuzzy_sha1::FuzzySha1;
+
+    #[test]
+    fn test_anf() {
+        let x = FBit::from_truncated_anf_variable(0.5, 0, usize::MAX);
+        let y = FBit::from_truncated_anf_variable(0.5, 1, usize::MAX);
+        let z = FBit::from_truncated_anf_variable(0.5, 2, usize::MAX);
+
+        let xy = (x & y).anf().unwrap().clone();
+        assert_eq!(xy.monomials(), &[vec![0, 1]]);
+        assert_eq!(xy.degree(), 2);
+
+        let or = (x | y).anf().unwrap().clone();
+        assert_eq!(or.monomials(), &[vec![0], vec![0, 1], vec![1]]);
+
+        let not = (!(x & y & z)).anf().unwrap().clone();
+        assert!(not.constant_term());
+        assert_eq!(not.degree(), 3);
+
+        assert_eq!(x.piecewise(y, z).anf().unwrap().degree(), 2);
+        assert_eq!(FBit::from(true).anf().unwrap().degree(), 0);
+        assert!(FBit::from_float(0.5).anf().is_none());
+        assert!((x & FBit::from_float(0.5)).anf().is_none());
+        assert!(FBit::from_variable(0.5, 0).anf().is_none());
+
+        let a = FBit::from_truncated_anf_variable(0.5, 3, 1);
+        let truncated = (a & x).anf().unwrap().clone();
+        assert!(truncated.monomials().is_empty());
+        assert!(truncated.is_truncated());
+
+        // Truncation is on unless asked otherwise
+        let product = (0..6)
+            .map(|i| FBit::from_anf_variable(0.5, 10 + i))
+            .reduce(|a, b| a & b)
+            .unwrap();
+        assert!(product.anf().unwrap().is_truncated());
+    }
+
+    #[test]
+    fn test_sha1_round_degrees() {
+        let mut message = "hello world"
+            .chars()
+            .map(|c| FInt8::from(c as u8 as usize))
+            .collect::<Vec<_>>();
+        message[0] = FInt8::build(|i| FBit::from_truncated_anf_variable(0.5, i, 8));
+
+        let mut hasher = FuzzySha1::with_rounds(2).with_trace();
+        hasher.update(&message);
+        let (digest, trace) = hasher.finalize_with_trace();
+
+        let degrees = round_degrees(&trace[0]);
+
+        assert_eq!(degrees.len(), 2);
+        assert_eq!(degrees[0].len(), 160);
+
+        // The first byte lands in the top byte of the first word, and only carries mix it
+        assert!(degrees[0][..24].iter().all(|d| *d == Some(0)));
+        assert_eq!(degrees[0][24], Some(1));
+        assert!(degrees[0][24..32].iter().all(|d| d.unwrap() <= 8));
+        assert!(degrees[0][32..].iter().all(|d| *d == Some(0)));
+        assert_eq!(degrees[1][32..64], degrees[0][..32]);
+
+        // Adding the constant initial word back in keeps the second word's low bits as they were
+        let digest_degrees = digest.degrees();
+        assert_eq!(digest_degrees.len(), 160);
+        assert!(digest_degrees[32..56].iter().all(|d| *d == Some(0)));
+        assert_eq!(digest_degrees[56], Some(1));
+    }
+}
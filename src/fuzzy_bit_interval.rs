@@ -0,0 +1,102 @@
+// Bounds on the probability of a bit being set that hold however the inputs are correlated.
+// Every operator returns the Fréchet bounds of its result, so the enclosure stays sound through
+// any circuit, at the cost of widening wherever bits are combined. The bounds are rounded
+// outward, so they also hold in spite of floating point error
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct FBitInterval {
+    lo: f64,
+    hi: f64,
+}
+
+impl FBitInterval {
+    pub const fn point(p: f64) -> FBitInterval {
+        FBitInterval { lo: p, hi: p }
+    }
+
+    pub fn new(lo: f64, hi: f64) -> FBitInterval {
+        assert!(0.0 <= lo && lo <= hi && hi <= 1.0);
+        FBitInterval { lo, hi }
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn contains(&self, p: f64) -> bool {
+        self.lo <= p && p <= self.hi
+    }
+
+    fn clamped(lo: f64, hi: f64) -> FBitInterval {
+        FBitInterval {
+            lo: lo.clamp(0.0, 1.0),
+            hi: hi.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn not(&self) -> FBitInterval {
+        FBitInterval::clamped(down(1.0 - self.hi), up(1.0 - self.lo))
+    }
+
+    pub fn and(&self, rhs: &FBitInterval) -> FBitInterval {
+        FBitInterval::clamped(down(down(self.lo + rhs.lo) - 1.0), self.hi.min(rhs.hi))
+    }
+
+    pub fn or(&self, rhs: &FBitInterval) -> FBitInterval {
+        FBitInterval::clamped(self.lo.max(rhs.lo), up(self.hi + rhs.hi))
+    }
+
+    // P(a ^ b) lies between |P(a) - P(b)| and min(P(a) + P(b), 2 - P(a) - P(b))
+    pub fn xor(&self, rhs: &FBitInterval) -> FBitInterval {
+        FBitInterval::clamped(
+            down(self.lo - rhs.hi).max(down(rhs.lo - self.hi)),
+            up(self.hi + rhs.hi).min(up(up(2.0 - self.lo) - rhs.lo)),
+        )
+    }
+
+    // s ? t : f is set with probability P(s & t) + P(!s & f). For a fixed P(s) = x both terms
+    // have Fréchet bounds, and the sum of those is convex (lower) or concave (upper) in x, so
+    // its extremes over the interval of s are at the ends or at a kink. At every kink the sum
+    // is the bound for t and f agreeing, which holds for any x, so a kink is taken as soon as
+    // it might lie within the interval of s despite rounding
+    pub fn piecewise(&self, t: &FBitInterval, f: &FBitInterval) -> FBitInterval {
+        let might_contain = |kink_lo: f64, kink_hi: f64| kink_lo <= self.hi && self.lo <= kink_hi;
+
+        let lower = |x: f64| down(down(down(x + t.lo) - 1.0).max(0.0) + down(f.lo - x).max(0.0));
+        let upper = |x: f64| up(x.min(t.hi) + up(1.0 - x).min(f.hi));
+
+        let floor = down(down(t.lo + f.lo) - 1.0).max(0.0);
+        let ceiling = up(t.hi + f.hi).min(1.0);
+
+        let lo = if might_contain(down(1.0 - t.lo), up(1.0 - t.lo)) || self.contains(f.lo) {
+            floor
+        } else {
+            lower(self.lo).min(lower(self.hi)).max(floor)
+        };
+
+        let hi = if might_contain(down(1.0 - f.hi), up(1.0 - f.hi)) || self.contains(t.hi) {
+            ceiling
+        } else {
+            upper(self.lo).max(upper(self.hi)).min(ceiling)
+        };
+
+        FBitInterval::clamped(lo, hi)
+    }
+}
+
+// Every bound is rounded outward after each operation, so that floating point error can only
+// widen the enclosure and never make it miss the true probability
+fn down(x: f64) -> f64 {
+    x.next_down()
+}
+
+fn up(x: f64) -> f64 {
+    x.next_up()
+}
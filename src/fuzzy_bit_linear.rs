@@ -0,0 +1,110 @@
+// Best linear approximation of a bit found so far: the bit agrees with the XOR of the input
+// variables in `mask` with the given correlation, 1 - 2 P(bit != mask . x). A negative
+// correlation means the bit follows the complement of the mask
+#[derive(PartialEq, Clone, Debug)]
+pub struct FBitLinear {
+    mask: Vec<usize>,
+    correlation: f64,
+}
+
+impl FBitLinear {
+    pub const fn constant(correlation: f64) -> FBitLinear {
+        FBitLinear {
+            mask: Vec::new(),
+            correlation,
+        }
+    }
+
+    pub fn variable(id: usize) -> FBitLinear {
+        FBitLinear {
+            mask: vec![id],
+            correlation: 1.0,
+        }
+    }
+
+    pub fn mask(&self) -> &[usize] {
+        &self.mask
+    }
+
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    fn xor_mask(lhs: &[usize], rhs: &[usize]) -> Vec<usize> {
+        let mut mask = Vec::with_capacity(lhs.len() + rhs.len());
+        let (mut i, mut j) = (0, 0);
+
+        while i < lhs.len() || j < rhs.len() {
+            match (lhs.get(i), rhs.get(j)) {
+                (Some(l), Some(r)) if l == r => {
+                    i += 1;
+                    j += 1;
+                }
+                (Some(l), Some(r)) if l < r => {
+                    mask.push(*l);
+                    i += 1;
+                }
+                (Some(l), None) => {
+                    mask.push(*l);
+                    i += 1;
+                }
+                (_, Some(r)) => {
+                    mask.push(*r);
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        mask
+    }
+
+    pub fn not(&self) -> FBitLinear {
+        FBitLinear {
+            mask: self.mask.clone(),
+            correlation: -self.correlation,
+        }
+    }
+
+    // Piling-up lemma
+    pub fn xor(&self, rhs: &FBitLinear) -> FBitLinear {
+        FBitLinear {
+            mask: Self::xor_mask(&self.mask, &rhs.mask),
+            correlation: self.correlation * rhs.correlation,
+        }
+    }
+
+    // a & b is approximated by whichever of 0, a, b or a ^ b it agrees with most, and that is
+    // then chained with the approximations of the operands. p and rhs_p are the probabilities
+    // of the operands being set
+    pub fn and(&self, p: f64, rhs: &FBitLinear, rhs_p: f64) -> FBitLinear {
+        let candidates = [
+            FBitLinear::constant(1.0 - 2.0 * p * rhs_p),
+            FBitLinear {
+                mask: self.mask.clone(),
+                correlation: (1.0 - 2.0 * p * (1.0 - rhs_p)) * self.correlation,
+            },
+            FBitLinear {
+                mask: rhs.mask.clone(),
+                correlation: (1.0 - 2.0 * (1.0 - p) * rhs_p) * rhs.correlation,
+            },
+            FBitLinear {
+                mask: Self::xor_mask(&self.mask, &rhs.mask),
+                correlation: (1.0 - 2.0 * (p + rhs_p - p * rhs_p))
+                    * self.correlation
+                    * rhs.correlation,
+            },
+        ];
+
+        candidates
+            .into_iter()
+            .reduce(|best, candidate| {
+                if candidate.correlation.abs() > best.correlation.abs() {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .unwrap()
+    }
+}
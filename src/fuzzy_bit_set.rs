@@ -5,8 +5,11 @@ use lazy_static::lazy_static;
 use std::{
     collections::{HashSet},
     sync::{Mutex},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
+static GATE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 lazy_static!{
     static ref FUZZZY_BIT_SET: Mutex<HashSet<FBit>> = Mutex::new(HashSet::from_iter([
         FBit::TRUE,
@@ -15,15 +18,24 @@ lazy_static!{
 }
 
 pub fn deduplicate_fbit<'a>(fbit: &'a FBit) -> &'a FBit {
+    GATE_COUNT.fetch_add(1, Ordering::Relaxed);
+
     let mut set = FUZZZY_BIT_SET.lock().unwrap();
-    if let Some(original) = set.get(&fbit) {
-        original
-    } else {
-        set.insert(*fbit);
-        fbit
+    match set.get(&fbit) {
+        Some(original) if original.same_analyses(fbit) => original,
+        // Computes the same function, but reached along a path that gave other analyses
+        Some(_) => fbit,
+        None => {
+            set.insert(*fbit);
+            fbit
+        }
     }
 }
 
 pub fn get_set_size() -> usize {
     FUZZZY_BIT_SET.lock().unwrap().len()
 }
+
+pub fn get_gate_count() -> usize {
+    GATE_COUNT.load(Ordering::Relaxed)
+}
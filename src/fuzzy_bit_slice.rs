@@ -0,0 +1,94 @@
+use crate::fuzzy_bit::FBit;
+
+use std::fmt;
+
+// Circuits over plain bit slices, shared by the fixed width FInt and the variable width FBigInt.
+// Missing bits read as zero, so the operands do not have to be as wide as the result
+
+pub(crate) fn ripple_add_carry(
+    a: &[FBit],
+    b: &[FBit],
+    mut carry: FBit,
+    width: usize,
+) -> (Vec<FBit>, FBit) {
+    let result = (0..width)
+        .map(|i| {
+            let lhs = a.get(i).unwrap_or_default();
+            let rhs = b.get(i).unwrap_or_default();
+            FBit::add_carry(lhs, rhs, &mut carry).clone()
+        })
+        .collect();
+
+    (result, carry)
+}
+
+pub(crate) fn ripple_add(a: &[FBit], b: &[FBit], carry: FBit, width: usize) -> Vec<FBit> {
+    ripple_add_carry(a, b, carry, width).0
+}
+
+// The returned bit is the borrow out of the top, which is set when a < b
+pub(crate) fn ripple_sub_borrow(a: &[FBit], b: &[FBit], width: usize) -> (Vec<FBit>, FBit) {
+    let inverted = (0..width)
+        .map(|i| (!b.get(i).unwrap_or_default()).clone())
+        .collect::<Vec<_>>();
+
+    let (result, carry) = ripple_add_carry(a, &inverted, FBit::TRUE, width);
+    (result, (!&carry).clone())
+}
+
+pub(crate) fn ripple_sub(a: &[FBit], b: &[FBit], width: usize) -> Vec<FBit> {
+    ripple_sub_borrow(a, b, width).0
+}
+
+// Restoring division. The quotient is as wide as the dividend and the remainder is as wide as
+// the divisor. Dividing by zero gives a quotient of all ones and leaves the dividend as the
+// remainder
+pub(crate) fn div_rem(dividend: &[FBit], divisor: &[FBit]) -> (Vec<FBit>, Vec<FBit>) {
+    assert!(!divisor.is_empty());
+
+    let width = divisor.len();
+    let mut quotient = vec![FBit::FALSE; dividend.len()];
+    let mut remainder = vec![FBit::FALSE; width];
+
+    for i in (0..dividend.len()).rev() {
+        // The bit shifted out of the top makes the partial remainder one bit wider than the
+        // divisor, in which case the divisor always fits
+        let overflow = remainder[width - 1].clone();
+        let shifted = (0..width)
+            .map(|j| if j == 0 { dividend[i].clone() } else { remainder[j - 1].clone() })
+            .collect::<Vec<_>>();
+
+        let (difference, borrow) = ripple_sub_borrow(&shifted, divisor, width);
+        let fits = &overflow | !&borrow;
+
+        remainder = (0..width)
+            .map(|j| fits.piecewise(&difference[j], &shifted[j]).clone())
+            .collect();
+        quotient[i] = fits.clone();
+    }
+
+    (quotient, remainder)
+}
+
+pub(crate) fn fmt_bits(f: &mut fmt::Formatter<'_>, name: &str, bits: &[FBit]) -> fmt::Result {
+    const CHUNK_SIZE: usize = 4;
+
+    writeln!(f, "{} [", name)?;
+
+    for (i, chunk) in bits.chunks(CHUNK_SIZE).enumerate() {
+        write!(
+            f,
+            "\t{}..{}: ",
+            CHUNK_SIZE * i,
+            CHUNK_SIZE * i + CHUNK_SIZE - 1
+        )?;
+
+        for bit in chunk {
+            write!(f, "{:?}, ", bit)?;
+        }
+
+        writeln!(f)?;
+    }
+
+    write!(f, "]")
+}
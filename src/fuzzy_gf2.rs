@@ -0,0 +1,61 @@
+use crate::fuzzy_int::FInt;
+
+impl<const L: usize> FInt<L> {
+    pub fn clmul(&self, rhs: &Self) -> (&Self, &Self) {
+        let mut low = FInt::<L>::init();
+        let mut high = FInt::<L>::init();
+
+        for i in 0..L {
+            low = *(&low ^ (self << i) * &rhs[i]);
+            high = *(&high ^ (self >> (L - i)) * &rhs[i]);
+        }
+
+        (&low, &high)
+    }
+
+    // Reduces low + high * x^L modulo x^L + poly, where poly holds the
+    // low coefficients of the reduction polynomial (0x1b for AES)
+    pub fn gf2_reduce<'a>(low: &'a Self, high: &'a Self, poly: &'a Self) -> &'a Self {
+        let mut low = low.clone();
+        let mut high = high.clone();
+
+        for i in (0..L).rev() {
+            let bit = high[i].clone();
+            low = *(&low ^ (poly << i) * &bit);
+            high = *(&high ^ (poly >> (L - i)) * &bit);
+        }
+
+        &low
+    }
+
+    pub fn gf2_mul(&self, rhs: &Self, poly: &Self) -> &Self {
+        let (low, high) = self.clmul(rhs);
+        FInt::gf2_reduce(low, high, poly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzzy_int::*;
+
+    #[test]
+    fn test_fint_clmul() {
+        let (low, high) = FInt8::from(0x57).clmul(FInt8::from(0x83));
+        assert_eq!(low.collapse(), 0x79);
+        assert_eq!(high.collapse(), 0x2b);
+
+        let (low, high) = FInt32::from(0xDEADBEEF).clmul(FInt32::from(0x12345678));
+        assert_eq!(low.collapse(), 0xde112da8);
+        assert_eq!(high.collapse(), 0xc42fde8);
+    }
+
+    #[test]
+    fn test_fint_gf2_mul() {
+        let poly = FInt8::from(0x1b);
+        let a = FInt8::from(0x57);
+
+        assert_eq!(a.gf2_mul(FInt8::from(0x83), poly).collapse(), 0xc1);
+        assert_eq!(a.gf2_mul(FInt8::from(0x13), poly).collapse(), 0xfe);
+        assert_eq!(a.gf2_mul(FInt8::from(0x02), poly).collapse(), 0xae);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::fuzzy_int::*;
+
+pub trait FuzzyHash<const N: usize>: Default {
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize = N;
+
+    fn update(&mut self, message: &[&FInt8]);
+
+    fn finalize(self) -> FInt<N>;
+
+    fn digest(message: &[&FInt8]) -> FInt<N> {
+        let mut hasher = Self::default();
+        hasher.update(message);
+        hasher.finalize()
+    }
+
+    // Digests are stored as big-endian 32-bit words, so the bytes need to be pulled out in that order
+    fn digest_bytes(digest: &FInt<N>) -> Vec<&FInt8> {
+        (0..N / 8)
+            .map(|j| {
+                let offset = 32 * (j / 4) + 8 * (3 - j % 4);
+                FInt8::build(|i| &digest[offset + i])
+            })
+            .collect()
+    }
+
+    fn digest_hex(digest: &FInt<N>) -> String {
+        let mut output = String::new();
+        for byte in Self::digest_bytes(digest) {
+            output.push_str(&format!("{:02x}", byte.collapse()));
+        }
+        output
+    }
+}
+
+#[derive(Clone)]
+pub struct RoundState {
+    pub round: usize,
+    pub d: Vec<FInt32>,
+    pub w: FInt32,
+}
+
+pub type BlockTrace = Vec<RoundState>;
+
+// Splits the message into blocks of the hasher's FuzzyHash::BLOCK_SIZE bytes, and pads the last
+// one with the message length in its final 8 bytes
+#[derive(Clone, Default)]
+pub struct MessageBuffer<const BLOCK_SIZE: usize> {
+    buffer: Vec<FInt8>,
+    length: usize,
+}
+
+impl<const BLOCK_SIZE: usize> MessageBuffer<BLOCK_SIZE> {
+    pub fn update(&mut self, message: &[&FInt8], mut compress: impl FnMut(&[&FInt8])) {
+        self.buffer.extend(message.iter().map(|byte| (*byte).clone()));
+        self.length += message.len();
+
+        while self.buffer.len() >= BLOCK_SIZE {
+            let block = self.buffer.drain(..BLOCK_SIZE).collect::<Vec<FInt8>>();
+            compress(&block.iter().collect::<Vec<&FInt8>>());
+        }
+    }
+
+    pub fn finalize(self, mut compress: impl FnMut(&[&FInt8])) {
+        let mut message = self.buffer.iter().collect::<Vec<&FInt8>>();
+        message.push(FInt8::from(0x80));
+
+        while message.len() % BLOCK_SIZE != BLOCK_SIZE - 8 {
+            message.push(FInt8::from(0x00));
+        }
+
+        let message_len = self.length * 8;
+        for i in 0..8 {
+            message.push(FInt8::from(message_len >> (56 - 8 * i)));
+        }
+
+        for block in message.chunks(BLOCK_SIZE) {
+            compress(block);
+        }
+    }
+}
+
+pub fn pack_words(message: &[&FInt8]) -> Vec<FInt32> {
+    message
+        .chunks(4)
+        .map(|chunk| {
+            FInt::combine(&[
+                chunk.get(3).unwrap_or(&&FInt8::default()),
+                chunk.get(2).unwrap_or(&&FInt8::default()),
+                chunk.get(1).unwrap_or(&&FInt8::default()),
+                chunk.get(0).unwrap_or(&&FInt8::default()),
+            ])
+        })
+        .collect::<Vec<_>>()
+}
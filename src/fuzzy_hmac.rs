@@ -0,0 +1,97 @@
+use crate::fuzzy_hash::*;
+use crate::fuzzy_int::*;
+
+pub fn fuzzy_hmac<const N: usize, H: FuzzyHash<N>>(key: &[&FInt8], message: &[&FInt8]) -> FInt<N> {
+    let block_size = H::BLOCK_SIZE;
+
+    let hashed_key;
+    let mut key = if key.len() > block_size {
+        hashed_key = H::digest(key);
+        H::digest_bytes(&hashed_key)
+    } else {
+        key.into_iter().cloned().collect::<Vec<&FInt8>>()
+    };
+
+    while key.len() < block_size {
+        key.push(FInt8::from(0x00));
+    }
+
+    let mut inner = key
+        .iter()
+        .map(|k| *k ^ FInt8::from(0x36))
+        .collect::<Vec<&FInt8>>();
+    inner.extend(message.into_iter().cloned());
+
+    let inner_hash = H::digest(&inner);
+
+    let mut outer = key
+        .iter()
+        .map(|k| *k ^ FInt8::from(0x5c))
+        .collect::<Vec<&FInt8>>();
+    outer.extend(H::digest_bytes(&inner_hash));
+
+    H::digest(&outer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_sha1::*;
+    use crate::fuzzy_sha256::*;
+
+    fn fuzzy_str(input: &str) -> Vec<&FInt8> {
+        input
+            .chars()
+            .map(|c| FInt8::from(c as u8 as usize))
+            .collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn test_fuzzy_hmac_sha1() {
+        let test_cases = vec![
+            (
+                "Jefe",
+                "what do ya want for nothing?",
+                "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            ),
+            (
+                "key",
+                "The quick brown fox jumps over the lazy dog",
+                "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9",
+            ),
+        ];
+
+        for (key, message, expected) in test_cases {
+            let result = fuzzy_hmac::<160, FuzzySha1>(&fuzzy_str(key), &fuzzy_str(message));
+            assert_eq!(FuzzySha1::digest_hex(&result), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_hmac_sha256() {
+        let long_key = "k".repeat(100);
+
+        let test_cases = vec![
+            (
+                "Jefe",
+                "what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                "key",
+                "The quick brown fox jumps over the lazy dog",
+                "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+            ),
+            (
+                long_key.as_str(),
+                "hello world",
+                "a1901e08710b671282f7fe064fb43829b5d76ed26d4f6e99e0fbf8fbf362de32",
+            ),
+        ];
+
+        for (key, message, expected) in test_cases {
+            let result = fuzzy_hmac::<256, FuzzySha256>(&fuzzy_str(key), &fuzzy_str(message));
+            assert_eq!(FuzzySha256::digest_hex(&result), expected);
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::fuzzy_bit::FBit;
+use crate::fuzzy_bit_slice;
 
 use std::fmt;
 use std::ops::Index;
-use std::ops::{Add, Mul, Neg, Shl, Shr, Sub};
+use std::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
 use std::ops::{BitAnd, BitOr, BitXor, Not};
 
 use array_init::array_init;
@@ -87,6 +88,40 @@ impl<const L: usize> Index<usize> for FInt<L> {
     }
 }
 
+impl<const L: usize> FInt<L> {
+    pub fn carrying_add(&self, rhs: &Self, carry: &FBit) -> (&Self, &FBit) {
+        let mut carry = carry.clone();
+        let result = FInt::<L>::build(|i| FBit::add_carry(&self[i], &rhs[i], &mut carry));
+        (result, &carry)
+    }
+
+    pub fn overflowing_add(&self, rhs: &Self) -> (&Self, &FBit) {
+        self.carrying_add(rhs, FBit::from(false))
+    }
+
+    pub fn overflowing_sub(&self, rhs: &Self) -> (&Self, &FBit) {
+        let (result, carry) = self.carrying_add(!rhs, FBit::from(true));
+        (result, !carry)
+    }
+
+    pub fn widening_mul(&self, rhs: &Self) -> (&Self, &Self) {
+        let mut low = FInt::<L>::init();
+        let mut high = FInt::<L>::init();
+
+        for i in 0..L {
+            let term = self * &rhs[i];
+
+            let (sum_low, carry) = low.carrying_add(term << i, FBit::from(false));
+            let (sum_high, _) = high.carrying_add(term >> (L - i), carry);
+
+            low = *sum_low;
+            high = *sum_high;
+        }
+
+        (&low, &high)
+    }
+}
+
 impl<const L: usize> Neg for &FInt<L> {
     type Output = Self;
 
@@ -99,8 +134,7 @@ impl<const L: usize> Add for &FInt<L> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let mut carry = FBit::FALSE;
-        FInt::<L>::build(|i| FBit::add_carry(&self[i], &rhs[i], &mut carry))
+        self.overflowing_add(rhs).0
     }
 }
 
@@ -128,6 +162,38 @@ impl<const L: usize> Shr<usize> for &FInt<L> {
     }
 }
 
+impl<const L: usize, const K: usize> Shl<&FInt<K>> for &FInt<L> {
+    type Output = Self;
+
+    fn shl(self, shift: &FInt<K>) -> Self::Output {
+        let mut result = self;
+        let mut step = 1.min(L);
+
+        for i in 0..K {
+            result = shift[i].piecewise_int(result << step, result);
+            step = (2 * step).min(L);
+        }
+
+        result
+    }
+}
+
+impl<const L: usize, const K: usize> Shr<&FInt<K>> for &FInt<L> {
+    type Output = Self;
+
+    fn shr(self, shift: &FInt<K>) -> Self::Output {
+        let mut result = self;
+        let mut step = 1.min(L);
+
+        for i in 0..K {
+            result = shift[i].piecewise_int(result >> step, result);
+            step = (2 * step).min(L);
+        }
+
+        result
+    }
+}
+
 impl<const L: usize> Mul<&FBit> for &FInt<L> {
     type Output = Self;
 
@@ -150,6 +216,36 @@ impl<const L: usize> Mul for &FInt<L> {
     }
 }
 
+impl<const L: usize> FInt<L> {
+    // Dividing by zero gives a quotient of all ones and leaves the dividend as the remainder,
+    // the returned bit is the probability that the divisor was zero
+    pub fn div_rem(&self, rhs: &Self) -> (&Self, &Self, &FBit) {
+        let (quotient, remainder) = fuzzy_bit_slice::div_rem(&self.bits, &rhs.bits);
+
+        (
+            FInt::<L>::build(|i| &quotient[i]),
+            FInt::<L>::build(|i| &remainder[i]),
+            rhs.eq(FInt::<L>::from(0)),
+        )
+    }
+}
+
+impl<const L: usize> Div for &FInt<L> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<const L: usize> Rem for &FInt<L> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
 impl<const L: usize> Not for &FInt<L> {
     type Output = Self;
 
@@ -192,30 +288,133 @@ impl<const L: usize> FInt<L> {
         let shift = shift.rem_euclid(L);
         (self >> shift) | (self << (L - shift))
     }
+
+    pub fn lrotate_by<const K: usize>(&self, shift: &FInt<K>) -> &Self {
+        let mut result = self;
+        let mut step = 1 % L;
+
+        for i in 0..K {
+            result = shift[i].piecewise_int(result.lrotate(step), result);
+            step = (2 * step) % L;
+        }
+
+        result
+    }
+
+    pub fn rrotate_by<const K: usize>(&self, shift: &FInt<K>) -> &Self {
+        let mut result = self;
+        let mut step = 1 % L;
+
+        for i in 0..K {
+            result = shift[i].piecewise_int(result.rrotate(step), result);
+            step = (2 * step) % L;
+        }
+
+        result
+    }
 }
 
-impl<const L: usize> fmt::Debug for FInt<L> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        const CHUNK_SIZE: usize = 4;
+impl<const L: usize> FInt<L> {
+    pub fn lt(&self, rhs: &Self) -> &FBit {
+        self.overflowing_sub(rhs).1
+    }
+
+    pub fn le(&self, rhs: &Self) -> &FBit {
+        !rhs.lt(self)
+    }
 
-        writeln!(f, "FInt{} [", L)?;
+    pub fn gt(&self, rhs: &Self) -> &FBit {
+        rhs.lt(self)
+    }
 
-        for (i, chunk) in self.bits.chunks(CHUNK_SIZE).enumerate() {
-            write!(
-                f,
-                "\t{}..{}: ",
-                CHUNK_SIZE * i,
-                CHUNK_SIZE * i + CHUNK_SIZE - 1
-            )?;
+    pub fn ge(&self, rhs: &Self) -> &FBit {
+        !self.lt(rhs)
+    }
 
-            for bit in chunk {
-                write!(f, "{:?}, ", bit)?;
-            }
+    pub fn eq(&self, rhs: &Self) -> &FBit {
+        let mut result = FBit::TRUE;
+
+        for i in 0..L {
+            result = *(&result & !(&self[i] ^ &rhs[i]));
+        }
+
+        &result
+    }
+
+    pub fn ne(&self, rhs: &Self) -> &FBit {
+        !self.eq(rhs)
+    }
+
+    pub fn lt_signed(&self, rhs: &Self) -> &FBit {
+        let lhs_sign = &self[L - 1];
+        let rhs_sign = &rhs[L - 1];
+
+        (lhs_sign ^ rhs_sign).piecewise(lhs_sign, self.lt(rhs))
+    }
+
+    pub fn le_signed(&self, rhs: &Self) -> &FBit {
+        !rhs.lt_signed(self)
+    }
+
+    pub fn gt_signed(&self, rhs: &Self) -> &FBit {
+        rhs.lt_signed(self)
+    }
+
+    pub fn ge_signed(&self, rhs: &Self) -> &FBit {
+        !self.lt_signed(rhs)
+    }
+}
+
+impl<const L: usize> FInt<L> {
+    // The counts go up to L, which has to fit in the W bits of the result
+    fn assert_count_fits<const W: usize>() {
+        assert!(W >= 64 || L < (1 << W), "FInt{} count does not fit in {} bits", L, W);
+    }
+
+    pub fn count_ones<const W: usize>(&self) -> &FInt<W> {
+        Self::assert_count_fits::<W>();
+
+        let mut result = FInt::<W>::init();
+
+        for i in 0..L {
+            result = *result.carrying_add(FInt::<W>::from(0), &self[i]).0;
+        }
+
+        &result
+    }
+
+    pub fn trailing_zeros<const W: usize>(&self) -> &FInt<W> {
+        Self::assert_count_fits::<W>();
 
-            writeln!(f)?;
+        let mut result = FInt::<W>::init();
+        let mut zeros = FBit::TRUE;
+
+        for i in 0..L {
+            zeros = *(&zeros & !&self[i]);
+            result = *result.carrying_add(FInt::<W>::from(0), &zeros).0;
+        }
+
+        &result
+    }
+
+    pub fn leading_zeros<const W: usize>(&self) -> &FInt<W> {
+        Self::assert_count_fits::<W>();
+
+        let mut result = FInt::<W>::init();
+        let mut zeros = FBit::TRUE;
+
+        for i in (0..L).rev() {
+            zeros = *(&zeros & !&self[i]);
+            result = *result.carrying_add(FInt::<W>::from(0), &zeros).0;
         }
 
-        write!(f, "]")
+        &result
+    }
+}
+
+impl<const L: usize> fmt::Debug for FInt<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fuzzy_bit_slice::fmt_bits(f, &format!("FInt{}", L), &self.bits)
     }
 }
 
@@ -240,6 +439,75 @@ mod tests {
         assert_eq!((c * d).collapse(), 2000000);
     }
 
+    #[test]
+    fn test_fint_division() {
+        let a = FInt8::from(200);
+        let b = FInt8::from(7);
+
+        assert_eq!((a / b).collapse(), 28);
+        assert_eq!((a % b).collapse(), 4);
+
+        let c = FInt8::from(0xFF);
+        let d = FInt8::from(0x81);
+
+        assert_eq!((c / d).collapse(), 1);
+        assert_eq!((c % d).collapse(), 0x7E);
+
+        let e = FInt32::from(123456);
+        let f = FInt32::from(1000);
+
+        assert_eq!((e / f).collapse(), 123);
+        assert_eq!((e % f).collapse(), 456);
+
+        let (quotient, remainder, divide_by_zero) = a.div_rem(FInt8::from(0));
+
+        assert_eq!(quotient.collapse(), 0xFF);
+        assert_eq!(remainder.collapse(), 200);
+        assert!(divide_by_zero.collapse());
+        assert!(!a.div_rem(b).2.collapse());
+    }
+
+    #[test]
+    fn test_fint_fuzzy_shift() {
+        let a = FInt8::from(0b1011_0110);
+
+        assert_eq!((a << FInt8::from(3)).collapse(), 0b1011_0000);
+        assert_eq!((a >> FInt8::from(3)).collapse(), 0b0001_0110);
+        assert_eq!((a << FInt8::from(9)).collapse(), 0);
+        assert_eq!((a >> FInt8::from(200)).collapse(), 0);
+        assert_eq!(a.lrotate_by(FInt8::from(3)).collapse(), 0b1011_0101);
+        assert_eq!(a.rrotate_by(FInt8::from(3)).collapse(), 0b1101_0110);
+        assert_eq!(a.lrotate_by(FInt8::from(11)).collapse(), 0b1011_0101);
+
+        let b = FInt32::from(0x12345678);
+        let shift = FInt::<5>::from(13);
+
+        assert_eq!(b.lrotate_by(shift).collapse(), 0x12345678_u32.rotate_left(13) as usize);
+        assert_eq!(b.rrotate_by(shift).collapse(), 0x12345678_u32.rotate_right(13) as usize);
+
+        let fuzzy_shift = FInt8::build(|i| match i {
+            1 => FBit::from_float(0.8),
+            _ => FBit::from(false),
+        });
+
+        assert_eq!((a << fuzzy_shift).collapse(), 0b1101_1000);
+    }
+
+    #[test]
+    fn test_fint_bit_counts() {
+        let a = FInt32::from(0x00F0_1000);
+
+        assert_eq!(a.count_ones::<6>().collapse(), 5);
+        assert_eq!(a.trailing_zeros::<6>().collapse(), 12);
+        assert_eq!(a.leading_zeros::<6>().collapse(), 8);
+
+        let b = FInt8::from(0);
+
+        assert_eq!(b.count_ones::<4>().collapse(), 0);
+        assert_eq!(b.trailing_zeros::<4>().collapse(), 8);
+        assert_eq!(b.leading_zeros::<4>().collapse(), 8);
+    }
+
     #[test]
     fn test_fint_resize() {
         let a = FInt8::from(42);
@@ -252,4 +520,80 @@ mod tests {
 
         assert_eq!(d.collapse(), 123456 & 0xFF);
     }
+
+    #[test]
+    fn test_fint_overflow() {
+        let a = FInt8::from(200);
+        let b = FInt8::from(100);
+
+        let (sum, carry) = a.overflowing_add(b);
+        assert_eq!(sum.collapse(), 44);
+        assert!(carry.collapse());
+
+        let (sum, carry) = b.overflowing_add(b);
+        assert_eq!(sum.collapse(), 200);
+        assert!(!carry.collapse());
+
+        let (sum, carry) = a.carrying_add(b, FBit::from(true));
+        assert_eq!(sum.collapse(), 45);
+        assert!(carry.collapse());
+
+        let (difference, borrow) = b.overflowing_sub(a);
+        assert_eq!(difference.collapse(), 156);
+        assert!(borrow.collapse());
+
+        let (difference, borrow) = a.overflowing_sub(b);
+        assert_eq!(difference.collapse(), 100);
+        assert!(!borrow.collapse());
+
+        let (low, high) = a.widening_mul(b);
+        assert_eq!(low.collapse(), 20000 & 0xFF);
+        assert_eq!(high.collapse(), 20000 >> 8);
+
+        let c = FInt32::from(0xDEADBEEF);
+        let d = FInt32::from(0x12345678);
+
+        let (low, high) = c.widening_mul(d);
+        assert_eq!(low.collapse(), (0xDEADBEEF_usize * 0x12345678) & 0xFFFFFFFF);
+        assert_eq!(high.collapse(), (0xDEADBEEF_usize * 0x12345678) >> 32);
+    }
+
+    #[test]
+    fn test_fint_comparison() {
+        let a = FInt8::from(20);
+        let b = FInt8::from(10);
+
+        assert!(b.lt(a).collapse());
+        assert!(!a.lt(b).collapse());
+        assert!(!a.lt(a).collapse());
+        assert!(a.le(a).collapse());
+        assert!(a.gt(b).collapse());
+        assert!(a.ge(a).collapse());
+        assert!(a.eq(a).collapse());
+        assert!(a.ne(b).collapse());
+        assert!(!a.eq(b).collapse());
+
+        let c = FInt8::from(0xF0);
+
+        assert!(b.lt(c).collapse());
+        assert!(c.lt_signed(b).collapse());
+        assert!(!b.lt_signed(c).collapse());
+        assert!(c.le_signed(c).collapse());
+        assert!(b.gt_signed(c).collapse());
+
+        let d = FInt8::build(|i| match i {
+            0 => FBit::from_float(0.3),
+            2 => FBit::from(true),
+            _ => FBit::from(false),
+        });
+
+        assert!(d.lt(FInt8::from(5)).collapse());
+        assert!(!d.lt(FInt8::from(4)).collapse());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fint_count_too_narrow() {
+        FInt8::from(0xFF).count_ones::<3>();
+    }
 }
@@ -0,0 +1,156 @@
+use crate::fuzzy_int::FInt;
+
+// All of the operands are expected to already be reduced below the modulus
+impl<const L: usize> FInt<L> {
+    pub fn mod_add(&self, rhs: &Self, modulus: &Self) -> &Self {
+        let (sum, carry) = self.overflowing_add(rhs);
+        let (reduced, borrow) = sum.overflowing_sub(modulus);
+        (carry | !borrow).piecewise_int(reduced, sum)
+    }
+
+    pub fn mod_sub(&self, rhs: &Self, modulus: &Self) -> &Self {
+        let (difference, borrow) = self.overflowing_sub(rhs);
+        borrow.piecewise_int(difference + modulus, difference)
+    }
+
+    pub fn mod_mul(&self, rhs: &Self, modulus: &Self) -> &Self {
+        let mut result = FInt::<L>::init();
+
+        for i in (0..L).rev() {
+            let doubled = result.mod_add(&result, modulus);
+            result = *rhs[i].piecewise_int(doubled.mod_add(self, modulus), doubled);
+        }
+
+        &result
+    }
+
+    pub fn mod_pow<const K: usize>(&self, exponent: &FInt<K>, modulus: &Self) -> &Self {
+        let mut result = *FInt::<L>::from(1);
+
+        for i in (0..K).rev() {
+            let squared = result.mod_mul(&result, modulus);
+            result = *exponent[i].piecewise_int(squared.mod_mul(self, modulus), squared);
+        }
+
+        &result
+    }
+}
+
+#[derive(Clone)]
+pub struct FMontgomery<const L: usize> {
+    modulus: FInt<L>,
+    inverse: FInt<L>,
+    r_squared: FInt<L>,
+}
+
+impl<const L: usize> FMontgomery<L> {
+    // The modulus has to be odd and greater than one. The constants are derived with the fuzzy
+    // operations themselves, so they are exact as long as the modulus is concrete
+    pub fn new(modulus: &FInt<L>) -> FMontgomery<L> {
+        // Newton's iteration doubles the number of correct bits of the inverse each step,
+        // and any odd number is its own inverse modulo 8
+        let mut inverse = modulus.clone();
+        let mut precision = 3;
+
+        while precision < L {
+            inverse = *(&inverse * (FInt::<L>::from(2) - modulus * &inverse));
+            precision *= 2;
+        }
+
+        let mut r_squared = *FInt::<L>::from(1);
+
+        for _ in 0..2 * L {
+            r_squared = *r_squared.mod_add(&r_squared, modulus);
+        }
+
+        FMontgomery {
+            modulus: modulus.clone(),
+            inverse: *(-&inverse),
+            r_squared,
+        }
+    }
+
+    pub fn reduce(&self, low: &FInt<L>, high: &FInt<L>) -> &FInt<L> {
+        let m = low * &self.inverse;
+        let (m_low, m_high) = m.widening_mul(&self.modulus);
+
+        let (_, carry) = low.overflowing_add(m_low);
+        let (t, overflow) = high.carrying_add(m_high, carry);
+
+        let (reduced, borrow) = t.overflowing_sub(&self.modulus);
+        (overflow | !borrow).piecewise_int(reduced, t)
+    }
+
+    pub fn to_montgomery(&self, value: &FInt<L>) -> &FInt<L> {
+        let (low, high) = value.widening_mul(&self.r_squared);
+        self.reduce(low, high)
+    }
+
+    pub fn from_montgomery(&self, value: &FInt<L>) -> &FInt<L> {
+        self.reduce(value, FInt::<L>::from(0))
+    }
+
+    pub fn mul(&self, lhs: &FInt<L>, rhs: &FInt<L>) -> &FInt<L> {
+        let (low, high) = lhs.widening_mul(rhs);
+        self.reduce(low, high)
+    }
+
+    pub fn pow<const K: usize>(&self, base: &FInt<L>, exponent: &FInt<K>) -> &FInt<L> {
+        let base = self.to_montgomery(base);
+        let mut result = self.to_montgomery(FInt::<L>::from(1));
+
+        for i in (0..K).rev() {
+            let squared = self.mul(result, result);
+            result = exponent[i].piecewise_int(self.mul(squared, base), squared);
+        }
+
+        self.from_montgomery(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_int::*;
+
+    #[test]
+    fn test_fint_modular() {
+        let p = FInt32::from(1000003);
+        let a = FInt32::from(123456);
+        let b = FInt32::from(987654);
+
+        assert_eq!(a.mod_add(b, p).collapse(), 111107);
+        assert_eq!(a.mod_sub(b, p).collapse(), 135805);
+        assert_eq!(a.mod_mul(b, p).collapse(), 446431);
+        assert_eq!(a.mod_pow(FInt::<17>::from(65537), p).collapse(), 146354);
+
+        let q = FInt32::from(0x7FFFFFFF);
+        let c = FInt32::from(0x7FFFFFF0);
+        let d = FInt32::from(0x7FFFFFF5);
+
+        assert_eq!(c.mod_add(d, q).collapse(), 2147483622);
+    }
+
+    #[test]
+    fn test_fint_montgomery() {
+        let q = FInt32::from(0x7FFFFFFF);
+        let a = FInt32::from(0x12345678);
+        let b = FInt32::from(0x7654321);
+
+        let montgomery = FMontgomery::new(q);
+
+        let product = montgomery.mul(montgomery.to_montgomery(a), montgomery.to_montgomery(b));
+        assert_eq!(montgomery.from_montgomery(product).collapse(), 1908789514);
+        assert_eq!(montgomery.pow(a, FInt::<17>::from(0x10001)).collapse(), 487020830);
+
+        let p = FInt32::from(1000003);
+        let montgomery = FMontgomery::new(p);
+
+        assert_eq!(
+            montgomery
+                .pow(FInt32::from(123456), FInt::<17>::from(65537))
+                .collapse(),
+            146354
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_bit_slice::{ripple_add, ripple_sub};
+use crate::fuzzy_int::FInt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Multiplier {
+    ShiftAdd,
+    Wallace,
+    Karatsuba,
+}
+
+impl Multiplier {
+    pub const ALL: [Multiplier; 3] = [
+        Multiplier::ShiftAdd,
+        Multiplier::Wallace,
+        Multiplier::Karatsuba,
+    ];
+}
+
+const KARATSUBA_THRESHOLD: usize = 16;
+
+fn shift_bits(a: &[FBit], shift: usize) -> Vec<FBit> {
+    let mut result = vec![FBit::FALSE; shift];
+    result.extend(a.iter().cloned());
+    result
+}
+
+// Reduces the partial products with layers of full adders until each column
+// has at most two bits left, then finishes with a single ripple carry adder
+fn wallace(a: &[FBit], b: &[FBit], width: usize) -> Vec<FBit> {
+    let mut columns: Vec<Vec<FBit>> = vec![Vec::new(); width];
+
+    for (i, lhs) in a.iter().enumerate() {
+        for (j, rhs) in b.iter().enumerate() {
+            if i + j < width {
+                columns[i + j].push((lhs & rhs).clone());
+            }
+        }
+    }
+
+    while columns.iter().any(|column| column.len() > 2) {
+        let mut reduced: Vec<Vec<FBit>> = vec![Vec::new(); width];
+
+        for (k, column) in columns.iter().enumerate() {
+            let mut triples = column.chunks_exact(3);
+
+            for triple in &mut triples {
+                let mut carry = triple[2].clone();
+                reduced[k].push(FBit::add_carry(&triple[0], &triple[1], &mut carry).clone());
+
+                if k + 1 < width {
+                    reduced[k + 1].push(carry);
+                }
+            }
+
+            reduced[k].extend(triples.remainder().iter().cloned());
+        }
+
+        columns = reduced;
+    }
+
+    let first = columns
+        .iter()
+        .map(|column| column.first().unwrap_or_default().clone())
+        .collect::<Vec<_>>();
+    let second = columns
+        .iter()
+        .map(|column| column.get(1).unwrap_or_default().clone())
+        .collect::<Vec<_>>();
+
+    ripple_add(&first, &second, FBit::FALSE, width)
+}
+
+// Full product of two equal length operands
+fn karatsuba(a: &[FBit], b: &[FBit]) -> Vec<FBit> {
+    let n = a.len();
+
+    if n <= KARATSUBA_THRESHOLD {
+        return wallace(a, b, 2 * n);
+    }
+
+    let h = n / 2;
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h);
+
+    let z0 = karatsuba(a0, b0);
+    let z2 = karatsuba(a1, b1);
+
+    let m = n - h + 1;
+    let sa = ripple_add(a0, a1, FBit::FALSE, m);
+    let sb = ripple_add(b0, b1, FBit::FALSE, m);
+
+    let z1 = karatsuba(&sa, &sb);
+    let z1 = ripple_sub(&z1, &z0, 2 * m);
+    let z1 = ripple_sub(&z1, &z2, 2 * m);
+
+    let result = ripple_add(&z0, &shift_bits(&z1, h), FBit::FALSE, 2 * n);
+    ripple_add(&result, &shift_bits(&z2, 2 * h), FBit::FALSE, 2 * n)
+}
+
+impl<const L: usize> FInt<L> {
+    fn to_bits(&self) -> Vec<FBit> {
+        (0..L).map(|i| self[i].clone()).collect()
+    }
+
+    pub fn mul_with(&self, rhs: &Self, multiplier: Multiplier) -> &Self {
+        match multiplier {
+            Multiplier::ShiftAdd => self * rhs,
+            Multiplier::Wallace => {
+                let product = wallace(&self.to_bits(), &rhs.to_bits(), L);
+                FInt::build(|i| &product[i])
+            }
+            Multiplier::Karatsuba => {
+                let product = karatsuba(&self.to_bits(), &rhs.to_bits());
+                FInt::build(|i| &product[i])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy_int::*;
+
+    #[test]
+    fn test_multipliers_agree() {
+        let test_cases: Vec<(usize, usize)> = vec![
+            (0, 0),
+            (20, 10),
+            (0xFFFFFFFF, 0xFFFFFFFF),
+            (0xDEADBEEF, 0x12345678),
+            (123456789, 987654321),
+        ];
+
+        for (a, b) in test_cases {
+            let expected = (a as u32).wrapping_mul(b as u32) as usize;
+            let wide_expected = (a as u64).wrapping_mul(b as u64) as usize;
+
+            for multiplier in Multiplier::ALL {
+                let result = FInt32::from(a).mul_with(FInt32::from(b), multiplier);
+                assert_eq!(result.collapse(), expected);
+
+                let result = FInt64::from(a).mul_with(FInt64::from(b), multiplier);
+                assert_eq!(result.collapse(), wide_expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,487 @@
+#[cfg(feature = "rational")]
+use num_bigint::BigInt;
+#[cfg(feature = "rational")]
+use num_rational::BigRational;
+#[cfg(feature = "rational")]
+use num_traits::{One, ToPrimitive, Zero};
+
+#[cfg(feature = "rational")]
+use std::borrow::Cow;
+#[cfg(feature = "rational")]
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+// The number type FBit probabilities are computed in. The choice is made with cargo features
+// (f32, rational, dyadic, log_odds, or f64 by default), so the same circuit can be evaluated
+// exactly and compared against the float pipeline. FBit only adds values whose sum is still a
+// probability, and only subtracts smaller values from larger ones. The operators take references,
+// as exact values can be too large to copy around.
+// Only the probability itself is computed in this type. The difference, interval, linear, stats
+// and avalanche analyses convert what they read from it and run in f64 whatever is selected
+pub trait Probability: Clone + PartialEq + PartialOrd + fmt::Debug + fmt::Display
+where
+    for<'a> &'a Self: Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>,
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_f64(p: f64) -> Self;
+
+    fn to_f64(&self) -> f64;
+
+    // 1 - p, taken before converting, so that it keeps its precision when p is close to 1
+    fn complement_to_f64(&self) -> f64 {
+        (&Self::ONE - self).to_f64()
+    }
+
+    // Taken from whichever of p and 1 - p is small, as that one keeps its precision
+    fn ln(&self) -> f64 {
+        let p = self.to_f64();
+        if p > 0.5 {
+            (-self.complement_to_f64()).ln_1p()
+        } else {
+            p.ln()
+        }
+    }
+
+    // ln(1 - p)
+    fn ln_complement(&self) -> f64 {
+        let p = self.to_f64();
+        if p < 0.5 {
+            (-p).ln_1p()
+        } else {
+            self.complement_to_f64().ln()
+        }
+    }
+
+    // ln(p / (1 - p)), which tells apart probabilities that round to 0 or 1 in to_f64
+    fn log_odds(&self) -> f64 {
+        self.ln() - self.ln_complement()
+    }
+}
+
+impl Probability for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f64(p: f64) -> Self {
+        p as f32
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
+impl Probability for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f64(p: f64) -> Self {
+        p
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+}
+
+// Exact fraction in lowest terms, with a numerator and denominator as large as they need to be.
+// Floats convert exactly, as they are all dyadic. Nothing is ever rounded, at the cost of the
+// denominators growing with every gate, which makes it slow on large circuits. Zero and one are
+// kept apart from the other values so that they can be constants
+#[cfg(feature = "rational")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Rational(RationalValue);
+
+#[cfg(feature = "rational")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum RationalValue {
+    Zero,
+    One,
+    Ratio(BigRational),
+}
+
+#[cfg(feature = "rational")]
+impl Rational {
+    pub fn new(num: impl Into<BigInt>, den: impl Into<BigInt>) -> Rational {
+        let den = den.into();
+        assert!(!den.is_zero());
+
+        Rational::from_ratio(BigRational::new(num.into(), den))
+    }
+
+    fn from_ratio(ratio: BigRational) -> Rational {
+        if ratio.is_zero() {
+            Self::ZERO
+        } else if ratio.is_one() {
+            Self::ONE
+        } else {
+            Rational(RationalValue::Ratio(ratio))
+        }
+    }
+
+    fn ratio(&self) -> Cow<'_, BigRational> {
+        match &self.0 {
+            RationalValue::Zero => Cow::Owned(BigRational::zero()),
+            RationalValue::One => Cow::Owned(BigRational::one()),
+            RationalValue::Ratio(ratio) => Cow::Borrowed(ratio),
+        }
+    }
+
+    pub fn numerator(&self) -> BigInt {
+        self.ratio().numer().clone()
+    }
+
+    pub fn denominator(&self) -> BigInt {
+        self.ratio().denom().clone()
+    }
+}
+
+#[cfg(feature = "rational")]
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Rational::from_ratio(&*self.ratio() + &*rhs.ratio())
+    }
+}
+
+#[cfg(feature = "rational")]
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Rational::from_ratio(&*self.ratio() - &*rhs.ratio())
+    }
+}
+
+#[cfg(feature = "rational")]
+impl Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rational::from_ratio(&*self.ratio() * &*rhs.ratio())
+    }
+}
+
+#[cfg(feature = "rational")]
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.ratio().cmp(&other.ratio()))
+    }
+}
+
+#[cfg(feature = "rational")]
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ratio())
+    }
+}
+
+#[cfg(feature = "rational")]
+impl Probability for Rational {
+    const ZERO: Self = Rational(RationalValue::Zero);
+    const ONE: Self = Rational(RationalValue::One);
+
+    fn from_f64(p: f64) -> Self {
+        Rational::from_ratio(BigRational::from_float(p).expect("probability must be finite"))
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.ratio().to_f64().unwrap()
+    }
+}
+
+// Fixed point with FRACTION_BITS binary places. Products are rounded to the nearest step, so
+// results are reproducible across platforms but not exact
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct Dyadic(i128);
+
+impl Dyadic {
+    // Leaves room for the product of two values of magnitude up to 2
+    const FRACTION_BITS: u32 = 62;
+}
+
+impl Add for &Dyadic {
+    type Output = Dyadic;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Dyadic(self.0 + rhs.0)
+    }
+}
+
+impl Sub for &Dyadic {
+    type Output = Dyadic;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Dyadic(self.0 - rhs.0)
+    }
+}
+
+impl Mul for &Dyadic {
+    type Output = Dyadic;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let half = 1 << (Dyadic::FRACTION_BITS - 1);
+        Dyadic((self.0 * rhs.0 + half) >> Dyadic::FRACTION_BITS)
+    }
+}
+
+impl fmt::Display for Dyadic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl Probability for Dyadic {
+    const ZERO: Self = Dyadic(0);
+    const ONE: Self = Dyadic(1 << Self::FRACTION_BITS);
+
+    fn from_f64(p: f64) -> Self {
+        Dyadic((p * (1u64 << Self::FRACTION_BITS) as f64).round() as i128)
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0 as f64 / (1u64 << Self::FRACTION_BITS) as f64
+    }
+}
+
+// Stores ln(p / (1 - p)). Both p and 1 - p can be recovered from it without cancellation, so
+// probabilities like 1e-30 or 1 - 1e-30 survive long chains of gates instead of saturating
+#[derive(PartialEq, PartialOrd, Clone, Copy, Debug)]
+pub struct LogOdds(f64);
+
+impl LogOdds {
+    // Relative rounding error allowed for when checking that results stay within [0, 1]
+    const SLACK: f64 = 1e-9;
+
+    fn from_logs(ln_p: f64, ln_q: f64) -> LogOdds {
+        LogOdds(ln_p - ln_q)
+    }
+
+    fn softplus(x: f64) -> f64 {
+        if x > 0.0 {
+            x + (-x).exp().ln_1p()
+        } else {
+            x.exp().ln_1p()
+        }
+    }
+
+    // ln(e^a + e^b)
+    fn log_add_exp(a: f64, b: f64) -> f64 {
+        let (high, low) = (a.max(b), a.min(b));
+        if high == f64::NEG_INFINITY {
+            high
+        } else {
+            high + (low - high).exp().ln_1p()
+        }
+    }
+
+    // ln(1 - e^a), with a clamped to at most 0
+    fn log_one_minus_exp(a: f64) -> f64 {
+        let a = a.min(0.0);
+        if a > -std::f64::consts::LN_2 {
+            (-a.exp_m1()).ln()
+        } else {
+            (-a.exp()).ln_1p()
+        }
+    }
+
+    // ln p
+    fn ln_p(self) -> f64 {
+        -Self::softplus(-self.0)
+    }
+
+    // ln (1 - p)
+    fn ln_q(self) -> f64 {
+        -Self::softplus(self.0)
+    }
+}
+
+impl Add for &LogOdds {
+    type Output = LogOdds;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if *rhs == LogOdds::ZERO {
+            return *self;
+        }
+
+        // The sum has to stay a probability, or it would silently come out as one
+        debug_assert!(
+            rhs.ln_p() <= self.ln_q() + LogOdds::SLACK * self.ln_q().abs().max(1.0),
+            "sum of probabilities above one"
+        );
+
+        LogOdds::from_logs(
+            LogOdds::log_add_exp(self.ln_p(), rhs.ln_p()),
+            self.ln_q() + LogOdds::log_one_minus_exp(rhs.ln_p() - self.ln_q()),
+        )
+    }
+}
+
+impl Sub for &LogOdds {
+    type Output = LogOdds;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        if *rhs == LogOdds::ZERO {
+            return *self;
+        }
+
+        debug_assert!(
+            rhs.ln_p() <= self.ln_p() + LogOdds::SLACK * self.ln_p().abs().max(1.0),
+            "difference of probabilities below zero"
+        );
+
+        LogOdds::from_logs(
+            self.ln_p() + LogOdds::log_one_minus_exp(rhs.ln_p() - self.ln_p()),
+            LogOdds::log_add_exp(self.ln_q(), rhs.ln_p()),
+        )
+    }
+}
+
+impl Mul for &LogOdds {
+    type Output = LogOdds;
+
+    // The logarithm of a product is the sum of the logarithms
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        let ln_p = self.ln_p() + rhs.ln_p();
+        LogOdds::from_logs(ln_p, LogOdds::log_one_minus_exp(ln_p))
+    }
+}
+
+impl fmt::Display for LogOdds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl Probability for LogOdds {
+    const ZERO: Self = LogOdds(f64::NEG_INFINITY);
+    const ONE: Self = LogOdds(f64::INFINITY);
+
+    fn from_f64(p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p));
+        LogOdds(p.ln() - (-p).ln_1p())
+    }
+
+    fn to_f64(&self) -> f64 {
+        1.0 / (1.0 + (-self.0).exp())
+    }
+
+    fn complement_to_f64(&self) -> f64 {
+        1.0 / (1.0 + self.0.exp())
+    }
+
+    fn ln(&self) -> f64 {
+        self.ln_p()
+    }
+
+    fn ln_complement(&self) -> f64 {
+        self.ln_q()
+    }
+
+    fn log_odds(&self) -> f64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_rational() {
+        let third = Rational::new(2, 6);
+
+        assert_eq!(third, Rational::new(-1, -3));
+        assert_eq!(&(&third + &third) + &third, Rational::ONE);
+        assert_eq!(&Rational::ONE - &(&third * &third), Rational::new(8, 9));
+        assert!(third < Rational::from_f64(0.5));
+        assert_eq!(Rational::from_f64(0.375), Rational::new(3, 8));
+        assert_eq!(Rational::from_f64(-6.0), Rational::new(-6, 1));
+        assert_eq!(Rational::from_f64(0.1).to_f64(), 0.1);
+        assert_eq!(Rational::from_f64(1e-30).to_f64(), 1e-30);
+        assert_eq!(Rational::from_f64(1.0), Rational::ONE);
+        assert_eq!(format!("{}", third), "1/3");
+
+        // XOR of independent bits, with no rounding however long the chain
+        let xor = |p: &Rational, q: &Rational| &(p + q) - &(&(p * q) + &(p * q));
+        let quarter = Rational::from_f64(0.25);
+        let mut p = quarter.clone();
+        for _ in 0..20 {
+            p = xor(&p, &quarter);
+        }
+        assert_eq!(p.to_f64(), 0.5 - 0.5_f64.powi(22));
+
+        // The denominator of 0.1 is 2^55, far past i128 after a few products
+        let tenth = Rational::from_f64(0.1);
+        let mut q = Rational::ONE;
+        for _ in 0..40 {
+            q = &q * &tenth;
+        }
+        assert!(q > Rational::ZERO);
+        assert!((q.to_f64() / 1e-40 - 1.0).abs() < 1e-12);
+        assert_eq!(&Rational::ONE - &(&Rational::ONE - &q), q);
+        assert_eq!((&Rational::ONE - &q).to_f64(), 1.0);
+        assert!((&Rational::ONE - &q).complement_to_f64() > 0.0);
+
+        let almost = &Rational::ONE - &Rational::from_f64(1e-30);
+        assert!((almost.ln() / -1e-30 - 1.0).abs() < 1e-12);
+        assert!((almost.ln_complement() / (1e-30_f64).ln() - 1.0).abs() < 1e-12);
+        assert!((almost.log_odds() / -(1e-30_f64).ln() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dyadic() {
+        let half = Dyadic::from_f64(0.5);
+
+        assert_eq!(&half + &half, Dyadic::ONE);
+        assert_eq!((&half * &half).to_f64(), 0.25);
+        assert_eq!(&Dyadic::ONE - &Dyadic::ONE, Dyadic::ZERO);
+        assert!(Dyadic::from_f64(-0.5) < Dyadic::ZERO);
+        assert_eq!(Dyadic::from_f64(0.1).to_f64(), 0.1);
+        assert_eq!(Dyadic::from_f64(0.75).complement_to_f64(), 0.25);
+        assert!(half.log_odds().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_log_odds() {
+        let approx_eq = |a: f64, b: f64| (a - b).abs() <= 1e-9 * b.abs().max(1.0);
+
+        let tiny = LogOdds::from_f64(1e-30);
+        let almost = &LogOdds::ONE - &tiny;
+
+        assert_eq!(almost.to_f64(), 1.0);
+        assert!(approx_eq(almost.complement_to_f64(), 1e-30));
+        assert!(approx_eq(almost.ln(), -1e-30));
+        assert!(approx_eq(almost.ln_complement(), (1e-30_f64).ln()));
+        assert!(approx_eq(almost.log_odds(), -(1e-30_f64).ln()));
+        assert!(approx_eq((&LogOdds::ONE - &almost).log_odds(), (1e-30_f64).ln()));
+        assert!(approx_eq((&almost * &almost).log_odds(), -(2e-30_f64).ln()));
+        assert!(approx_eq((&tiny * &tiny).log_odds(), (1e-60_f64).ln()));
+        assert!(tiny > LogOdds::ZERO && almost < LogOdds::ONE);
+
+        let quarter = LogOdds::from_f64(0.25);
+        let half = LogOdds::from_f64(0.5);
+
+        assert!(approx_eq((&quarter + &half).to_f64(), 0.75));
+        assert!(approx_eq((&half - &quarter).to_f64(), 0.25));
+        assert!(approx_eq((&half * &half).to_f64(), 0.25));
+        assert_eq!(&LogOdds::ONE * &half, half);
+        assert_eq!(&LogOdds::ZERO * &half, LogOdds::ZERO);
+        assert_eq!((&LogOdds::ONE - &LogOdds::ONE).to_f64(), 0.0);
+        assert!(approx_eq(half.log_odds(), 0.0));
+        assert_eq!(&quarter + &LogOdds::from_f64(0.75), LogOdds::ONE);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn test_log_odds_above_one() {
+        let _ = &LogOdds::from_f64(0.75) + &LogOdds::from_f64(0.5);
+    }
+}
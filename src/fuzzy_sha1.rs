@@ -1,108 +1,152 @@
+use crate::fuzzy_hash::*;
 use crate::fuzzy_int::*;
 use array_init::array_init;
 
-pub fn fuzzy_sha1(message_slice: &[&FInt8]) -> FInt<160> {
-    let mut h = [
-        FInt32::from(0x67452301),
-        FInt32::from(0xEFCDAB89),
-        FInt32::from(0x98BADCFE),
-        FInt32::from(0x10325476),
-        FInt32::from(0xC3D2E1F0),
-    ];
-
-    let mut message = message_slice.into_iter().cloned().collect::<Vec<&FInt8>>();
-    message.push(FInt8::from(0x80));
-
-    while message.len() % 64 != 56 {
-        message.push(FInt8::from(0x00));
+fn compress(
+    h: &mut [FInt32; 5],
+    block: &[&FInt8],
+    rounds: usize,
+    trace: &mut Option<Vec<BlockTrace>>,
+) {
+    let chunk = pack_words(block);
+    let mut d: [&FInt32; 5] = array_init(|i| &h[i]);
+
+    let mut w: [&FInt32; 80] = array_init(|_| &FInt32::init());
+
+    for i in 0..16 {
+        *w[i] = chunk[i];
     }
 
-    let message_len = message_slice.len() * 8;
-    for i in 0..8 {
-        message.push(FInt8::from(message_len >> (56 - 8 * i)));
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).lrotate(1);
     }
 
-    let words = message
-        .chunks(4)
-        .map(|chunk| {
-            FInt::combine(&[
-                chunk.get(3).unwrap_or(&&FInt8::default()),
-                chunk.get(2).unwrap_or(&&FInt8::default()),
-                chunk.get(1).unwrap_or(&&FInt8::default()),
-                chunk.get(0).unwrap_or(&&FInt8::default()),
-            ])
-        })
-        .collect::<Vec<_>>();
-
-    for chunk in words.chunks(16) {
-        let mut d = h;
-
-        let mut w: [&FInt32; 80] = array_init(|_| &FInt32::init());
-
-        for i in 0..16 {
-            *w[i] = chunk[i];
-        }
+    let mut block_trace = BlockTrace::new();
 
-        for i in 16..80 {
-            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).lrotate(1);
-        }
+    for i in 0..rounds {
+        let f;
+        let k;
+        match i {
+            0..=19 => {
+                f = (d[1] & d[2]) | (!d[1] & d[3]);
+                k = FInt32::from(0x5A827999);
+            }
+
+            20..=39 => {
+                f = d[1] ^ d[2] ^ d[3];
+                k = FInt32::from(0x6ED9EBA1);
+            }
+
+            40..=59 => {
+                f = (d[1] & d[2]) | (d[1] & d[3]) | (d[2] & d[3]);
+                k = FInt32::from(0x8F1BBCDC);
+            }
 
-        for i in 0..80 {
-            let f;
-            let k;
-            match i {
-                0..=19 => {
-                    f = (d[1] & d[2]) | (!d[1] & d[3]);
-                    k = FInt32::from(0x5A827999);
-                }
-
-                20..=39 => {
-                    f = d[1] ^ d[2] ^ d[3];
-                    k = FInt32::from(0x6ED9EBA1);
-                }
-
-                40..=59 => {
-                    f = (d[1] & d[2]) | (d[1] & d[3]) | (d[2] & d[3]);
-                    k = FInt32::from(0x8F1BBCDC);
-                }
-
-                60..=79 => {
-                    f = d[1] ^ d[2] ^ d[3];
-                    k = FInt32::from(0xCA62C1D6);
-                }
-
-                _ => {
-                    panic!();
-                }
+            60..=79 => {
+                f = d[1] ^ d[2] ^ d[3];
+                k = FInt32::from(0xCA62C1D6);
             }
 
-            let temp = d[0].lrotate(5) + f + d[4] + k + &w[i];
-            d[4] = d[3];
-            d[3] = d[2];
-            d[2] = d[1].lrotate(30);
-            d[1] = d[0];
-            d[0] = temp;
+            _ => {
+                panic!();
+            }
         }
 
-        for i in 0..5 {
-            h[i] = h[i] + d[i];
+        let temp = d[0].lrotate(5) + f + d[4] + k + &w[i];
+        d[4] = d[3];
+        d[3] = d[2];
+        d[2] = d[1].lrotate(30);
+        d[1] = d[0];
+        d[0] = temp;
+
+        if trace.is_some() {
+            block_trace.push(RoundState {
+                round: i,
+                d: d.iter().map(|x| (*x).clone()).collect(),
+                w: w[i].clone(),
+            });
         }
     }
 
-    FInt::combine(&h)
+    if let Some(trace) = trace {
+        trace.push(block_trace);
+    }
+
+    *h = array_init(|i| *(&h[i] + d[i]));
 }
 
-pub fn fuzzy_sha1_str(hash: FInt<160>) -> String {
-    let mut output = String::new();
-    for int in &hash.split::<32>() {
-        output.push_str(&format!("{:08x}", int.collapse()));
+#[derive(Clone)]
+pub struct FuzzySha1 {
+    h: [FInt32; 5],
+    buffer: MessageBuffer<{ <FuzzySha1 as FuzzyHash<160>>::BLOCK_SIZE }>,
+    rounds: usize,
+    trace: Option<Vec<BlockTrace>>,
+}
+
+impl Default for FuzzySha1 {
+    fn default() -> Self {
+        FuzzySha1 {
+            h: [
+                *FInt32::from(0x67452301),
+                *FInt32::from(0xEFCDAB89),
+                *FInt32::from(0x98BADCFE),
+                *FInt32::from(0x10325476),
+                *FInt32::from(0xC3D2E1F0),
+            ],
+            buffer: MessageBuffer::default(),
+            rounds: 80,
+            trace: None,
+        }
     }
-    output
+}
+
+impl FuzzyHash<160> for FuzzySha1 {
+    const BLOCK_SIZE: usize = 64;
+
+    fn update(&mut self, message: &[&FInt8]) {
+        let (h, rounds, trace) = (&mut self.h, self.rounds, &mut self.trace);
+        self.buffer.update(message, |block| compress(h, block, rounds, trace));
+    }
+
+    fn finalize(self) -> FInt<160> {
+        self.finalize_with_trace().0
+    }
+}
+
+impl FuzzySha1 {
+    pub fn with_rounds(rounds: usize) -> Self {
+        assert!(rounds <= 80);
+
+        FuzzySha1 {
+            rounds,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn finalize_with_trace(mut self) -> (FInt<160>, Vec<BlockTrace>) {
+        let (h, rounds, trace) = (&mut self.h, self.rounds, &mut self.trace);
+        self.buffer.finalize(|block| compress(h, block, rounds, trace));
+
+        (
+            FInt::<32>::combine::<5>(&array_init(|i| &self.h[i])),
+            self.trace.unwrap_or_default(),
+        )
+    }
+}
+
+pub fn fuzzy_sha1(message_slice: &[&FInt8]) -> FInt<160> {
+    FuzzySha1::digest(message_slice)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fuzzy_bit::FBit;
 
     #[test]
     fn test_fuzzy_sha1() {
@@ -125,7 +169,98 @@ mod tests {
                 .map(|c| FInt8::from(c as u8 as usize))
                 .collect::<Vec<_>>();
             let result = fuzzy_sha1(input_fuzzy.as_slice());
-            assert_eq!(fuzzy_sha1_str(result), expected);
+            assert_eq!(FuzzySha1::digest_hex(&result), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_sha1_streaming() {
+        let fuzzy_str = |input: &str| {
+            input
+                .chars()
+                .map(|c| FInt8::from(c as u8 as usize))
+                .collect::<Vec<_>>()
+        };
+
+        let prefix = fuzzy_str("The quick brown fox jumps over the lazy dog");
+
+        let mut hasher = FuzzySha1::default();
+        for byte in &prefix {
+            hasher.update(&[*byte]);
+        }
+
+        let test_cases = vec![
+            (
+                "The quick brown fox jumps over the lazy dog",
+                "86c842aa0249527aad022bab2f8a9b4d77b82b12",
+            ),
+            (" and the lazy cog", "62fed0c1c8f74702a5a038c6189c36ed22b2b06a"),
+        ];
+
+        for (suffix, expected) in test_cases {
+            let mut experiment = hasher.clone();
+            experiment.update(&fuzzy_str(suffix));
+            assert_eq!(FuzzySha1::digest_hex(&experiment.finalize()), expected);
         }
     }
+
+    #[test]
+    fn test_fuzzy_sha1_trace() {
+        let input_fuzzy = "hello world"
+            .chars()
+            .map(|c| FInt8::from(c as u8 as usize))
+            .collect::<Vec<_>>();
+
+        let mut hasher = FuzzySha1::default().with_trace();
+        hasher.update(&input_fuzzy);
+        let (result, trace) = hasher.finalize_with_trace();
+
+        assert_eq!(
+            FuzzySha1::digest_hex(&result),
+            "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"
+        );
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].len(), 80);
+        assert_eq!(trace[0][0].w.collapse(), 0x68656c6c);
+
+        let mut reduced = FuzzySha1::with_rounds(20).with_trace();
+        reduced.update(&input_fuzzy);
+        let (_, trace) = reduced.finalize_with_trace();
+
+        assert_eq!(trace[0].len(), 20);
+        assert_eq!(trace[0][19].round, 19);
+        assert_eq!(trace[0][19].d.len(), 5);
+    }
+
+    #[test]
+    fn test_fuzzy_sha1_difference() {
+        let mut input_fuzzy = "hello world"
+            .chars()
+            .map(|c| FInt8::from(c as u8 as usize))
+            .collect::<Vec<_>>();
+
+        let byte = input_fuzzy[3];
+        input_fuzzy[3] = FInt8::build(|i| {
+            if i == 0 {
+                FBit::from_difference(byte[i].probability(), 1.0)
+            } else {
+                &byte[i]
+            }
+        });
+
+        let mut hasher = FuzzySha1::with_rounds(2).with_trace();
+        hasher.update(&input_fuzzy);
+        let (_, trace) = hasher.finalize_with_trace();
+
+        assert_eq!(trace[0][0].w[0].difference(), 1.0);
+        assert_eq!(trace[0][0].d[0][0].difference(), 1.0);
+        assert_eq!(trace[0][0].d[1][0].difference(), 0.0);
+        assert_eq!(trace[0][1].d[1][0].difference(), 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fuzzy_sha1_too_many_rounds() {
+        FuzzySha1::with_rounds(81);
+    }
 }
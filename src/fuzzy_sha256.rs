@@ -1,6 +1,13 @@
+use crate::fuzzy_hash::*;
 use crate::fuzzy_int::*;
+use array_init::array_init;
 
-pub fn fuzzy_sha256(message_slice: &[&FInt8]) -> FInt<256> {
+fn compress(
+    h: &mut [FInt32; 8],
+    block: &[&FInt8],
+    rounds: usize,
+    trace: &mut Option<Vec<BlockTrace>>,
+) {
     let k: [&FInt32; 64] = [
         FInt32::from(0x428a2f98),
         FInt32::from(0x71374491),
@@ -68,97 +75,134 @@ pub fn fuzzy_sha256(message_slice: &[&FInt8]) -> FInt<256> {
         FInt32::from(0xc67178f2),
     ];
 
-    let mut h = [
-        FInt32::from(0x6a09e667),
-        FInt32::from(0xbb67ae85),
-        FInt32::from(0x3c6ef372),
-        FInt32::from(0xa54ff53a),
-        FInt32::from(0x510e527f),
-        FInt32::from(0x9b05688c),
-        FInt32::from(0x1f83d9ab),
-        FInt32::from(0x5be0cd19),
-    ];
+    let chunk = pack_words(block);
+    let mut d: [&FInt32; 8] = array_init(|i| &h[i]);
 
-    let mut message = message_slice.into_iter().cloned().collect::<Vec<&FInt8>>();
-    message.push(FInt8::from(0x80));
+    let mut w: [&FInt32; 80] = array_init(|_| &FInt32::default());
 
-    while message.len() % 64 != 56 {
-        message.push(FInt8::from(0x00));
+    for i in 0..16 {
+        *w[i] = chunk[i];
     }
 
-    let message_len = message_slice.len() * 8;
-    for i in 0..8 {
-        message.push(FInt8::from(message_len >> (56 - 8 * i)));
+    for i in 16..80 {
+        w[i] = {
+            let p = w[i - 16] + w[i - 7];
+
+            let w0 = w[i - 15];
+            let t0 = w0.rrotate(7) ^ w0.rrotate(18) ^ (w0 >> 3);
+
+            let w1 = w[i - 2];
+            let t1 = w1.rrotate(17) ^ w1.rrotate(19) ^ (w1 >> 10);
+
+            p + t0 + t1
+        };
     }
 
-    let words = message
-        .chunks(4)
-        .map(|chunk| {
-            FInt::combine(&[
-                chunk.get(3).unwrap_or(&&FInt8::default()),
-                chunk.get(2).unwrap_or(&&FInt8::default()),
-                chunk.get(1).unwrap_or(&&FInt8::default()),
-                chunk.get(0).unwrap_or(&&FInt8::default()),
-            ])
-        })
-        .collect::<Vec<_>>();
-
-    for chunk in words.chunks(16) {
-        let mut d = h;
-
-        let mut w: [&FInt32; 80] = array_init::array_init(|_| &FInt32::default());
-
-        for i in 0..16 {
-            *w[i] = chunk[i];
-        }
+    let mut block_trace = BlockTrace::new();
 
-        for i in 16..80 {
-            w[i] = {
-                let p = w[i - 16] + w[i - 7];
+    for i in 0..rounds {
+        let a1 = d[4].rrotate(6) ^ d[4].rrotate(11) ^ d[4].rrotate(25);
+        let b1 = (d[4] & d[5]) ^ (!d[4] & d[6]);
+        let t1 = d[7] + a1 + b1 + k[i] + w[i];
 
-                let w0 = w[i - 15];
-                let t0 = w0.rrotate(7) ^ w0.rrotate(18) ^ (w0 >> 3);
+        let a2 = d[0].rrotate(2) ^ d[0].rrotate(13) ^ d[0].rrotate(22);
+        let b2 = (d[0] & d[1]) ^ (d[0] & d[2]) ^ (d[1] & d[2]);
+        let t2 = a2 + b2;
 
-                let w1 = w[i - 2];
-                let t1 = w1.rrotate(17) ^ w1.rrotate(19) ^ (w1 >> 10);
+        d[7] = d[6];
+        d[6] = d[5];
+        d[5] = d[4];
+        d[4] = d[3] + t1;
+        d[3] = d[2];
+        d[2] = d[1];
+        d[1] = d[0];
+        d[0] = t1 + t2;
 
-                p + t0 + t1
-            };
+        if trace.is_some() {
+            block_trace.push(RoundState {
+                round: i,
+                d: d.iter().map(|x| (*x).clone()).collect(),
+                w: w[i].clone(),
+            });
         }
+    }
 
-        for i in 0..64 {
-            let a1 = d[4].rrotate(6) ^ d[4].rrotate(11) ^ d[4].rrotate(25);
-            let b1 = (d[4] & d[5]) ^ (!d[4] & d[6]);
-            let t1 = d[7] + a1 + b1 + k[i] + w[i];
-
-            let a2 = d[0].rrotate(2) ^ d[0].rrotate(13) ^ d[0].rrotate(22);
-            let b2 = (d[0] & d[1]) ^ (d[0] & d[2]) ^ (d[1] & d[2]);
-            let t2 = a2 + b2;
-
-            d[7] = d[6];
-            d[6] = d[5];
-            d[5] = d[4];
-            d[4] = d[3] + t1;
-            d[3] = d[2];
-            d[2] = d[1];
-            d[1] = d[0];
-            d[0] = t1 + t2;
-        }
+    if let Some(trace) = trace {
+        trace.push(block_trace);
+    }
+
+    *h = array_init(|i| *(&h[i] + d[i]));
+}
 
-        for i in 0..8 {
-            h[i] = h[i] + d[i];
+#[derive(Clone)]
+pub struct FuzzySha256 {
+    h: [FInt32; 8],
+    buffer: MessageBuffer<{ <FuzzySha256 as FuzzyHash<256>>::BLOCK_SIZE }>,
+    rounds: usize,
+    trace: Option<Vec<BlockTrace>>,
+}
+
+impl Default for FuzzySha256 {
+    fn default() -> Self {
+        FuzzySha256 {
+            h: [
+                *FInt32::from(0x6a09e667),
+                *FInt32::from(0xbb67ae85),
+                *FInt32::from(0x3c6ef372),
+                *FInt32::from(0xa54ff53a),
+                *FInt32::from(0x510e527f),
+                *FInt32::from(0x9b05688c),
+                *FInt32::from(0x1f83d9ab),
+                *FInt32::from(0x5be0cd19),
+            ],
+            buffer: MessageBuffer::default(),
+            rounds: 64,
+            trace: None,
         }
     }
+}
+
+impl FuzzyHash<256> for FuzzySha256 {
+    const BLOCK_SIZE: usize = 64;
+
+    fn update(&mut self, message: &[&FInt8]) {
+        let (h, rounds, trace) = (&mut self.h, self.rounds, &mut self.trace);
+        self.buffer.update(message, |block| compress(h, block, rounds, trace));
+    }
 
-    FInt::combine(&h)
+    fn finalize(self) -> FInt<256> {
+        self.finalize_with_trace().0
+    }
 }
 
-pub fn fuzzy_sha256_str(hash: FInt<256>) -> String {
-    let mut output = String::new();
-    for int in &hash.split::<32>() {
-        output.push_str(&format!("{:08x}", int.collapse()));
+impl FuzzySha256 {
+    pub fn with_rounds(rounds: usize) -> Self {
+        assert!(rounds <= 64);
+
+        FuzzySha256 {
+            rounds,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn finalize_with_trace(mut self) -> (FInt<256>, Vec<BlockTrace>) {
+        let (h, rounds, trace) = (&mut self.h, self.rounds, &mut self.trace);
+        self.buffer.finalize(|block| compress(h, block, rounds, trace));
+
+        (
+            FInt::<32>::combine::<8>(&array_init(|i| &self.h[i])),
+            self.trace.unwrap_or_default(),
+        )
     }
-    output
+}
+
+pub fn fuzzy_sha256(message_slice: &[&FInt8]) -> FInt<256> {
+    FuzzySha256::digest(message_slice)
 }
 
 #[cfg(test)]
@@ -192,7 +236,80 @@ mod tests {
                 .map(|c| FInt8::from(c as u8 as usize))
                 .collect::<Vec<_>>();
             let result = fuzzy_sha256(&input_fuzzy);
-            assert_eq!(fuzzy_sha256_str(result), expected);
+            assert_eq!(FuzzySha256::digest_hex(&result), expected);
         }
     }
+
+    #[test]
+    fn test_fuzzy_sha256_streaming() {
+        let fuzzy_str = |input: &str| {
+            input
+                .chars()
+                .map(|c| FInt8::from(c as u8 as usize))
+                .collect::<Vec<_>>()
+        };
+
+        let prefix = fuzzy_str("The quick brown fox jumps over the lazy dog");
+
+        let mut hasher = FuzzySha256::default();
+        for byte in &prefix {
+            hasher.update(&[*byte]);
+        }
+
+        let test_cases = vec![
+            (
+                "The quick brown fox jumps over the lazy dog",
+                "cce97087f477acafa60f6e74bfe5dd17d9d099b87fb93a2fb779dbb527a08fe7",
+            ),
+            (
+                " and the lazy cog",
+                "94e3b1d7e8568e88ae9b6024ec007cac44ed4511b478067c263d2ba341f38720",
+            ),
+        ];
+
+        for (suffix, expected) in test_cases {
+            let mut experiment = hasher.clone();
+            experiment.update(&fuzzy_str(suffix));
+            assert_eq!(FuzzySha256::digest_hex(&experiment.finalize()), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_sha256_trace() {
+        let input_fuzzy = "abc"
+            .chars()
+            .map(|c| FInt8::from(c as u8 as usize))
+            .collect::<Vec<_>>();
+
+        let mut hasher = FuzzySha256::default().with_trace();
+        hasher.update(&input_fuzzy);
+        let (result, trace) = hasher.finalize_with_trace();
+
+        assert_eq!(
+            FuzzySha256::digest_hex(&result),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].len(), 64);
+        assert_eq!(trace[0][0].w.collapse(), 0x61626380);
+        assert_eq!(trace[0][0].d[0].collapse(), 0x5d6aebcd);
+        assert_eq!(trace[0][0].d[4].collapse(), 0xfa2a4622);
+        assert_eq!(trace[0][1].d[0].collapse(), 0x5a6ad9ad);
+        assert_eq!(trace[0][1].d[4].collapse(), 0x78ce7989);
+
+        let mut reduced = FuzzySha256::with_rounds(16).with_trace();
+        reduced.update(&input_fuzzy);
+        let (_, trace) = reduced.finalize_with_trace();
+
+        assert_eq!(trace[0].len(), 16);
+        assert_eq!(trace[0][15].round, 15);
+        assert_eq!(trace[0][15].d.len(), 8);
+        assert_eq!(trace[0][1].d[0].collapse(), 0x5a6ad9ad);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fuzzy_sha256_too_many_rounds() {
+        FuzzySha256::with_rounds(65);
+    }
 }
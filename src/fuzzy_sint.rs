@@ -0,0 +1,252 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_int::FInt;
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+
+#[derive(Clone)]
+pub struct FSInt<const L: usize> {
+    int: FInt<L>,
+}
+
+pub type FSInt8 = FSInt<8>;
+pub type FSInt16 = FSInt<16>;
+pub type FSInt32 = FSInt<32>;
+pub type FSInt64 = FSInt<64>;
+
+impl<const L: usize> FSInt<L> {
+    pub fn from_int(int: &FInt<L>) -> &FSInt<L> {
+        &FSInt { int: int.clone() }
+    }
+
+    pub fn from<'a>(n: i64) -> &'a FSInt<L> {
+        FSInt::from_int(FInt::build(|i| FBit::from(0 != (n >> i.min(63)) & 1)))
+    }
+
+    pub fn as_int(&self) -> &FInt<L> {
+        &self.int
+    }
+
+    pub fn sign(&self) -> &FBit {
+        &self.int[L - 1]
+    }
+
+    pub fn resize<const L2: usize>(self) -> FSInt<L2> {
+        *FSInt::from_int(FInt::build(|i| if i < L { &self.int[i] } else { self.sign() }))
+    }
+
+    pub fn abs(&self) -> &FInt<L> {
+        self.sign().piecewise_int(-&self.int, &self.int)
+    }
+
+    pub fn collapse(&self) -> i64 {
+        let value = self.int.collapse() as i64;
+
+        if L < 64 {
+            (value << (64 - L)) >> (64 - L)
+        } else {
+            value
+        }
+    }
+}
+
+impl<const L: usize> FSInt<L> {
+    pub fn lt(&self, rhs: &Self) -> &FBit {
+        self.int.lt_signed(&rhs.int)
+    }
+
+    pub fn le(&self, rhs: &Self) -> &FBit {
+        self.int.le_signed(&rhs.int)
+    }
+
+    pub fn gt(&self, rhs: &Self) -> &FBit {
+        self.int.gt_signed(&rhs.int)
+    }
+
+    pub fn ge(&self, rhs: &Self) -> &FBit {
+        self.int.ge_signed(&rhs.int)
+    }
+}
+
+impl<const L: usize> FSInt<L> {
+    fn is_min(&self) -> &FBit {
+        self.int.eq(FInt::<L>::from((1 as usize) << (L - 1)))
+    }
+
+    fn is_minus_one(&self) -> &FBit {
+        self.int.eq(!FInt::<L>::from(0))
+    }
+
+    pub fn overflowing_add(&self, rhs: &Self) -> (&Self, &FBit) {
+        let result = self + rhs;
+        let overflow = (self.sign() ^ result.sign()) & (rhs.sign() ^ result.sign());
+        (result, overflow)
+    }
+
+    pub fn overflowing_sub(&self, rhs: &Self) -> (&Self, &FBit) {
+        let result = self - rhs;
+        let overflow = (self.sign() ^ rhs.sign()) & (self.sign() ^ result.sign());
+        (result, overflow)
+    }
+
+    pub fn overflowing_mul(&self, rhs: &Self) -> (&Self, &FBit) {
+        let (low, high) = self.int.widening_mul(&rhs.int);
+
+        // Correct the unsigned high half for two's complement operands, the product fits
+        // if the high half is just the sign extension of the low half
+        let high = high
+            - self.sign().piecewise_int(&rhs.int, FInt::<L>::from(0))
+            - rhs.sign().piecewise_int(&self.int, FInt::<L>::from(0));
+        let extension = FInt::<L>::build(|_| &low[L - 1]);
+
+        (FSInt::from_int(low), high.ne(extension))
+    }
+
+    // Division truncates towards zero, the returned bits are the probability of overflow (MIN / -1)
+    // and the probability that the divisor was zero
+    pub fn div_rem(&self, rhs: &Self) -> (&Self, &Self, &FBit, &FBit) {
+        let (quotient, remainder, divide_by_zero) = self.abs().div_rem(rhs.abs());
+
+        let quotient = (self.sign() ^ rhs.sign()).piecewise_int(-quotient, quotient);
+        let remainder = self.sign().piecewise_int(-remainder, remainder);
+        let overflow = self.is_min() & rhs.is_minus_one();
+
+        (
+            FSInt::from_int(quotient),
+            FSInt::from_int(remainder),
+            overflow,
+            divide_by_zero,
+        )
+    }
+}
+
+impl<const L: usize> Neg for &FSInt<L> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        FSInt::from_int(-&self.int)
+    }
+}
+
+impl<const L: usize> Add for &FSInt<L> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        FSInt::from_int(&self.int + &rhs.int)
+    }
+}
+
+impl<const L: usize> Sub for &FSInt<L> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        FSInt::from_int(&self.int - &rhs.int)
+    }
+}
+
+impl<const L: usize> Mul for &FSInt<L> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        FSInt::from_int(&self.int * &rhs.int)
+    }
+}
+
+impl<const L: usize> Div for &FSInt<L> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<const L: usize> Rem for &FSInt<L> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl<const L: usize> Shl<usize> for &FSInt<L> {
+    type Output = Self;
+
+    fn shl(self, shift: usize) -> Self::Output {
+        FSInt::from_int(&self.int << shift)
+    }
+}
+
+impl<const L: usize> Shr<usize> for &FSInt<L> {
+    type Output = Self;
+
+    fn shr(self, shift: usize) -> Self::Output {
+        FSInt::from_int(FInt::build(|i| {
+            if i.saturating_add(shift) < L {
+                &self.int[i + shift]
+            } else {
+                self.sign()
+            }
+        }))
+    }
+}
+
+impl<const L: usize> fmt::Debug for FSInt<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FSInt{} {:?}", L, self.int)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fsint_arithmetic() {
+        let a = FSInt8::from(-20);
+        let b = FSInt8::from(7);
+
+        assert_eq!((a + b).collapse(), -13);
+        assert_eq!((a - b).collapse(), -27);
+        assert_eq!((a * b).collapse(), -140 + 256);
+        assert_eq!((a / b).collapse(), -2);
+        assert_eq!((a % b).collapse(), -6);
+        assert_eq!((b / a).collapse(), 0);
+        assert_eq!((-a).collapse(), 20);
+
+        let c = FSInt32::from(-123456);
+        let d = FSInt32::from(-1000);
+
+        assert_eq!((c * d).collapse(), 123456000);
+        assert_eq!((c / d).collapse(), 123);
+        assert_eq!((c % d).collapse(), -456);
+    }
+
+    #[test]
+    fn test_fsint_shift_and_resize() {
+        let a = FSInt8::from(-96);
+
+        assert_eq!((a >> 3).collapse(), -12);
+        assert_eq!((a >> 100).collapse(), -1);
+        assert_eq!((FSInt8::from(96) >> 3).collapse(), 12);
+        assert_eq!(a.clone().resize::<32>().collapse(), -96);
+        assert_eq!(FSInt32::from(-200).resize::<8>().collapse(), 56);
+    }
+
+    #[test]
+    fn test_fsint_overflow() {
+        let max = FSInt8::from(127);
+        let min = FSInt8::from(-128);
+        let one = FSInt8::from(1);
+        let minus_one = FSInt8::from(-1);
+
+        assert!(max.overflowing_add(one).1.collapse());
+        assert!(!max.overflowing_add(minus_one).1.collapse());
+        assert!(min.overflowing_sub(one).1.collapse());
+        assert!(!min.overflowing_sub(minus_one).1.collapse());
+        assert!(min.overflowing_mul(minus_one).1.collapse());
+        assert!(FSInt8::from(16).overflowing_mul(FSInt8::from(8)).1.collapse());
+        assert!(!FSInt8::from(-16).overflowing_mul(FSInt8::from(8)).1.collapse());
+        assert!(min.div_rem(minus_one).2.collapse());
+        assert!(min.div_rem(FSInt8::from(0)).3.collapse());
+    }
+}
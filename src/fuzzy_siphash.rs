@@ -0,0 +1,134 @@
+use crate::fuzzy_int::*;
+
+fn sip_round(v: &mut [&FInt64; 4]) {
+    v[0] = v[0] + v[1];
+    v[1] = v[1].lrotate(13);
+    v[1] = v[1] ^ v[0];
+    v[0] = v[0].lrotate(32);
+
+    v[2] = v[2] + v[3];
+    v[3] = v[3].lrotate(16);
+    v[3] = v[3] ^ v[2];
+
+    v[0] = v[0] + v[3];
+    v[3] = v[3].lrotate(21);
+    v[3] = v[3] ^ v[0];
+
+    v[2] = v[2] + v[1];
+    v[1] = v[1].lrotate(17);
+    v[1] = v[1] ^ v[2];
+    v[2] = v[2].lrotate(32);
+}
+
+pub fn fuzzy_siphash(
+    c_rounds: usize,
+    d_rounds: usize,
+    key: &FInt<128>,
+    message_slice: &[&FInt8],
+) -> FInt64 {
+    let [k0, k1] = key.clone().split::<64>();
+
+    let mut v = [
+        k0 ^ FInt64::from(0x736f6d6570736575),
+        k1 ^ FInt64::from(0x646f72616e646f6d),
+        k0 ^ FInt64::from(0x6c7967656e657261),
+        k1 ^ FInt64::from(0x7465646279746573),
+    ];
+
+    let mut message = message_slice.into_iter().cloned().collect::<Vec<&FInt8>>();
+
+    while message.len() % 8 != 7 {
+        message.push(FInt8::from(0x00));
+    }
+
+    message.push(FInt8::from(message_slice.len() & 0xff));
+
+    let words = message
+        .chunks(8)
+        .map(|chunk| {
+            FInt::combine(&[
+                chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    for m in &words {
+        v[3] = v[3] ^ m;
+
+        for _ in 0..c_rounds {
+            sip_round(&mut v);
+        }
+
+        v[0] = v[0] ^ m;
+    }
+
+    v[2] = v[2] ^ FInt64::from(0xff);
+
+    for _ in 0..d_rounds {
+        sip_round(&mut v);
+    }
+
+    *(v[0] ^ v[1] ^ v[2] ^ v[3])
+}
+
+pub fn fuzzy_siphash24(key: &FInt<128>, message_slice: &[&FInt8]) -> FInt64 {
+    fuzzy_siphash(2, 4, key, message_slice)
+}
+
+pub fn fuzzy_siphash13(key: &FInt<128>, message_slice: &[&FInt8]) -> FInt64 {
+    fuzzy_siphash(1, 3, key, message_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_key() -> FInt<128> {
+        let bytes = (0..16).map(|i| FInt8::from(i as usize)).collect::<Vec<_>>();
+        *FInt::<128>::build(|i| &bytes[i / 8][i % 8])
+    }
+
+    #[test]
+    fn test_fuzzy_siphash24() {
+        let test_cases = vec![
+            (0, 0x726fdb47dd0e0e31),
+            (1, 0x74f839c593dc67fd),
+            (7, 0xab0200f58b01d137),
+            (8, 0x93f5f5799a932462),
+            (15, 0xa129ca6149be45e5),
+            (63, 0x958a324ceb064572),
+        ];
+
+        let key = reference_key();
+
+        for (length, expected) in test_cases {
+            let input_fuzzy = (0..length)
+                .map(|i| FInt8::from(i as usize))
+                .collect::<Vec<_>>();
+            let result = fuzzy_siphash24(&key, &input_fuzzy);
+            assert_eq!(result.collapse(), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_siphash13() {
+        let test_cases = vec![
+            (0, 0xabac0158050fc4dc),
+            (1, 0xc9f49bf37d57ca93),
+            (7, 0xd3927d989bb11140),
+            (8, 0x369095118d299a8e),
+            (15, 0xd320d86d2a519956),
+            (63, 0x9d199062b7bbb3a8),
+        ];
+
+        let key = reference_key();
+
+        for (length, expected) in test_cases {
+            let input_fuzzy = (0..length)
+                .map(|i| FInt8::from(i as usize))
+                .collect::<Vec<_>>();
+            let result = fuzzy_siphash13(&key, &input_fuzzy);
+            assert_eq!(result.collapse(), expected);
+        }
+    }
+}
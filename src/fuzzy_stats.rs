@@ -0,0 +1,275 @@
+use crate::fuzzy_bit::FBit;
+use crate::fuzzy_int::FInt;
+
+const MAX_DISTRIBUTION_BITS: usize = 24;
+
+impl<const L: usize> FInt<L> {
+    // Exact distribution of the number of set bits, assuming the bits are independent
+    pub fn count_ones_distribution(&self) -> Vec<f64> {
+        let mut distribution = vec![0.0; L + 1];
+        distribution[0] = 1.0;
+
+        for i in 0..L {
+            let (p, q) = (self[i].probability(), self[i].complement_probability());
+
+            for k in (0..=i).rev() {
+                distribution[k + 1] += distribution[k] * p;
+                distribution[k] *= q;
+            }
+        }
+
+        distribution
+    }
+
+    // Exact distributions of the number of zeros before the first set bit, counting from the
+    // bottom or the top, assuming the bits are independent
+    pub fn trailing_zeros_distribution(&self) -> Vec<f64> {
+        self.zeros_run_distribution((0..L).collect())
+    }
+
+    pub fn leading_zeros_distribution(&self) -> Vec<f64> {
+        self.zeros_run_distribution((0..L).rev().collect())
+    }
+
+    fn zeros_run_distribution(&self, order: Vec<usize>) -> Vec<f64> {
+        let mut distribution = vec![0.0; L + 1];
+        let mut all_zero = 1.0;
+
+        for (k, i) in order.into_iter().enumerate() {
+            distribution[k] = all_zero * self[i].probability();
+            all_zero *= self[i].complement_probability();
+        }
+
+        distribution[L] = all_zero;
+        distribution
+    }
+
+    // Probability of every value, assuming the bits are independent
+    pub fn distribution(&self) -> Vec<f64> {
+        assert!(L <= MAX_DISTRIBUTION_BITS);
+
+        let mut distribution = vec![1.0];
+
+        for i in 0..L {
+            let (p, q) = (self[i].probability(), self[i].complement_probability());
+            let unset = distribution.iter().map(|r| r * q);
+            let set = distribution.iter().map(|r| r * p);
+            distribution = unset.chain(set).collect();
+        }
+
+        distribution
+    }
+
+    pub fn expected_value(&self) -> f64 {
+        (0..L)
+            .map(|i| self[i].probability() * 2.0_f64.powi(i as i32))
+            .sum()
+    }
+
+    pub fn variance(&self) -> f64 {
+        (0..L)
+            .map(|i| {
+                let (p, q) = (self[i].probability(), self[i].complement_probability());
+                p * q * 4.0_f64.powi(i as i32)
+            })
+            .sum()
+    }
+
+    // The k most likely values in order, assuming the bits are independent. Starting from the
+    // collapsed value, bits are flipped from the least to the most certain, so the search
+    // never has to look at more than k candidates per result
+    pub fn most_likely(&self, k: usize) -> Vec<(usize, f64)> {
+        assert!(L <= 64);
+
+        let mut order = (0..L).collect::<Vec<_>>();
+        let ratio = |i: usize| {
+            let (p, q) = (self[i].probability(), self[i].complement_probability());
+            p.min(q) / p.max(q)
+        };
+        order.sort_by(|a, b| ratio(*b).partial_cmp(&ratio(*a)).unwrap());
+
+        let base = (0..L)
+            .map(|i| self[i].probability().max(self[i].complement_probability()))
+            .product::<f64>();
+
+        let mut results = Vec::new();
+        let mut frontier = vec![(base, self.collapse(), None::<usize>)];
+
+        while results.len() < k {
+            let Some(best) = (0..frontier.len())
+                .max_by(|a, b| frontier[*a].0.partial_cmp(&frontier[*b].0).unwrap())
+            else {
+                break;
+            };
+
+            let (probability, value, last) = frontier.swap_remove(best);
+            if probability <= 0.0 {
+                break;
+            }
+
+            results.push((value, probability));
+
+            let next = last.map_or(0, |j| j + 1);
+            if next < L {
+                let bit = (1 as usize) << order[next];
+                frontier.push((probability * ratio(order[next]), value ^ bit, Some(next)));
+
+                if let Some(j) = last {
+                    let previous = (1 as usize) << order[j];
+                    frontier.push((
+                        probability / ratio(order[j]) * ratio(order[next]),
+                        value ^ previous ^ bit,
+                        Some(next),
+                    ));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+impl<const L: usize> FInt<L> {
+    // Bits that are identical to or the complement of an earlier bit carry no extra information
+    fn distinct_bits(&self) -> Vec<&FBit> {
+        let mut distinct: Vec<&FBit> = Vec::new();
+
+        for i in 0..L {
+            let bit = &self[i];
+            if !distinct
+                .iter()
+                .any(|other| bit == *other || bit.is_complement(other))
+            {
+                distinct.push(bit);
+            }
+        }
+
+        distinct
+    }
+
+    // Sum of the entropy of every bit, which is an upper bound on the joint entropy
+    pub fn entropy(&self) -> f64 {
+        (0..L).map(|i| self[i].entropy()).sum()
+    }
+
+    // Joint estimate that uses the bit hashes to drop repeated bits, treating the rest as independent
+    pub fn joint_entropy(&self) -> f64 {
+        self.distinct_bits().iter().map(|bit| bit.entropy()).sum()
+    }
+
+    pub fn min_entropy(&self) -> f64 {
+        self.distinct_bits()
+            .iter()
+            .map(|bit| bit.min_entropy())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzzy_bit::FBit;
+    use crate::fuzzy_int::*;
+
+    #[test]
+    fn test_count_ones_distribution() {
+        let a = FInt8::build(|i| match i {
+            0 | 1 => FBit::from_float(0.5),
+            2 => FBit::from(true),
+            _ => FBit::from(false),
+        });
+
+        let distribution = a.count_ones_distribution();
+
+        assert_eq!(distribution.len(), 9);
+        assert_eq!(distribution[0], 0.0);
+        assert_eq!(distribution[1], 0.25);
+        assert_eq!(distribution[2], 0.5);
+        assert_eq!(distribution[3], 0.25);
+        assert_eq!(distribution[4], 0.0);
+    }
+
+    #[test]
+    fn test_zeros_distribution() {
+        let a = FInt8::build(|i| match i {
+            0 => FBit::from_float(0.5),
+            1 => FBit::from(true),
+            _ => FBit::from(false),
+        });
+
+        let trailing = a.trailing_zeros_distribution();
+
+        assert_eq!(trailing.len(), 9);
+        assert_eq!(trailing[0], 0.5);
+        assert_eq!(trailing[1], 0.5);
+        assert_eq!(trailing[8], 0.0);
+
+        let leading = a.leading_zeros_distribution();
+
+        assert_eq!(leading[6], 1.0);
+        assert_eq!(leading.iter().sum::<f64>(), 1.0);
+        assert_eq!(FInt8::from(0).leading_zeros_distribution()[8], 1.0);
+    }
+
+    #[test]
+    fn test_value_distribution() {
+        let a = FInt8::build(|i| match i {
+            0 => FBit::from_float(0.9),
+            1 => FBit::from_float(0.3),
+            _ => FBit::from(false),
+        });
+
+        let approx_eq = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+        let distribution = a.distribution();
+
+        assert_eq!(distribution.len(), 256);
+        assert!(approx_eq(distribution[0], 0.07));
+        assert!(approx_eq(distribution[1], 0.63));
+        assert!(approx_eq(distribution[2], 0.03));
+        assert!(approx_eq(distribution[3], 0.27));
+        assert!(approx_eq(distribution.iter().sum(), 1.0));
+
+        assert!(approx_eq(a.expected_value(), 1.5));
+        assert!(approx_eq(a.variance(), 0.93));
+
+        let most_likely = a.most_likely(5);
+
+        assert_eq!(most_likely.len(), 4);
+        assert_eq!(
+            most_likely
+                .iter()
+                .map(|(value, _)| *value)
+                .collect::<Vec<_>>(),
+            vec![1, 3, 0, 2]
+        );
+        assert!(approx_eq(most_likely[1].1, 0.27));
+    }
+
+    #[test]
+    fn test_entropy() {
+        let x = FBit::from_float(0.5);
+
+        assert_eq!(x.entropy(), 1.0);
+        assert_eq!(x.min_entropy(), 1.0);
+        assert_eq!(FBit::from(true).entropy(), 0.0);
+        assert_eq!(FBit::from(false).min_entropy(), 0.0);
+
+        let a = FInt8::build(|i| match i {
+            0 | 1 => x,
+            2 => !x,
+            _ => FBit::from(false),
+        });
+
+        assert_eq!(a.entropy(), 3.0);
+        assert_eq!(a.joint_entropy(), 1.0);
+        assert_eq!(a.min_entropy(), 1.0);
+
+        let b = FInt8::build(|i| match i {
+            0 => FBit::from_float(0.9),
+            _ => FBit::from(false),
+        });
+
+        assert!((b.min_entropy() + 0.9_f64.log2()).abs() < 1e-12);
+        assert!(b.entropy() < 1.0);
+    }
+}
@@ -1,20 +1,59 @@
 #![feature(const_trait_impl)]
 #![feature(generic_const_exprs)]
 
+pub mod fuzzy_bit_anf;
 pub mod fuzzy_bit_hash;
+pub mod fuzzy_bit_interval;
+pub mod fuzzy_bit_linear;
 pub mod fuzzy_bit_set;
+pub mod fuzzy_bit_slice;
+pub mod fuzzy_probability;
 pub mod fuzzy_bit;
 pub mod fuzzy_int;
+pub mod fuzzy_mul;
+pub mod fuzzy_adder;
+pub mod fuzzy_sint;
+pub mod fuzzy_big_int;
+pub mod fuzzy_modular;
+pub mod fuzzy_gf2;
+pub mod fuzzy_stats;
+pub mod fuzzy_hash;
 pub mod fuzzy_sha1;
 pub mod fuzzy_sha256;
+pub mod fuzzy_siphash;
+pub mod fuzzy_hmac;
+pub mod fuzzy_avalanche;
 
 use fuzzy_int::*;
+use fuzzy_mul::Multiplier;
 
 use crate::fuzzy_bit::FBit;
 
 use fuzzy_sha1::*;
 use fuzzy_sha256::*;
 
+use std::time::Instant;
+
+fn benchmark_multipliers<const L: usize>() {
+    let a = FInt::<L>::build(|i| FBit::from_float(0.25 + 0.5 * ((i % 3) as f64) / 2.0));
+    let b = FInt::<L>::build(|i| FBit::from_float(0.75 - 0.5 * ((i % 5) as f64) / 4.0));
+
+    for multiplier in Multiplier::ALL {
+        let gates = fuzzy_bit_set::get_gate_count();
+        let start = Instant::now();
+
+        a.mul_with(b, multiplier);
+
+        println!(
+            "FInt{} {:?}: {} gates in {:?}",
+            L,
+            multiplier,
+            fuzzy_bit_set::get_gate_count() - gates,
+            start.elapsed()
+        );
+    }
+}
+
 fn main() {
     let tap = 0.5;
 
@@ -68,4 +107,8 @@ fn main() {
     dbg!(b);
     dbg!(c);
 
+    println!("");
+    println!("MULTIPLIERS");
+    benchmark_multipliers::<32>();
+    benchmark_multipliers::<64>();
 }